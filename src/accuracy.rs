@@ -0,0 +1,55 @@
+//! Works out how likely attacks are to hit.
+
+use crate::Point;
+use crate::bn;
+use crate::entity::{En, PUZZLE};
+use crate::status::StatusKind;
+
+/// Hit chance lost by attacking while standing on ice.
+const ICE_PENALTY: f64 = 0.2;
+/// Hit chance lost by attacking after moving in the same turn.
+const MOVE_PENALTY: f64 = 0.15;
+/// Hit chance lost by attacking while slowed.
+const SLOW_PENALTY: f64 = 0.1;
+/// Hit chance gained by attacking something that can't move out of the way.
+const HELD_BONUS: f64 = 0.25;
+
+/// Returns the change to the hit chance of an entity attacking from the given position. Moved
+/// is true if it has moved this turn, including by sliding.
+pub fn attacker_mod(map: &bn::Map<En>, en: &En, pos: Point, moved: bool) -> f64 {
+    let mut change = 0.0;
+    if map.get_map(pos).is_some_and(|t| t.slippery) {
+        change -= ICE_PENALTY;
+    }
+    if moved {
+        change -= MOVE_PENALTY;
+    }
+    if en.status(StatusKind::Slow).is_some() {
+        change -= SLOW_PENALTY;
+    }
+    change
+}
+
+/// Returns the change to the hit chance of anything attacking the entity.
+pub fn target_mod(en: &En) -> f64 {
+    if en.status(StatusKind::Stun).is_some() || en.status(StatusKind::Frozen).is_some() {
+        HELD_BONUS
+    } else {
+        0.0
+    }
+}
+
+/// Returns the chance of an attack with the given base accuracy hitting after the change.
+/// Nothing changes accuracy in puzzles, so they always play out the same way.
+pub fn chance(base: f64, change: f64) -> f64 {
+    if unsafe { PUZZLE.is_some() } {
+        base
+    } else {
+        (base + change).clamp(0.0, 1.0)
+    }
+}
+
+/// Returns the chance as a whole percentage.
+pub fn percent(chance: f64) -> String {
+    format!("{:.0}%", chance * 100.0)
+}
@@ -1,11 +1,9 @@
 //! Contains objects used in attacking.
-use crate::{Point, Vfx};
+use crate::{Point, Vfx, status};
 use crossterm::style;
 use std::collections::HashMap;
 
-pub const THICC_FOUR_POS_ATK: [char; 4] = ['═', '║', '═', '║'];
 pub const FOUR_POS_ATK: [char; 4] = ['-', '|', '-', '|'];
-pub const EIGHT_POS_ATK: [char; 8] = ['╱', '-', '╲', '|', '|', '╲', '-', '╱'];
 
 mod damage;
 pub use damage::*;
@@ -37,6 +35,8 @@ pub enum Effect {
     ///
     /// Returns a list of commands to be executed.
     Other(Box<dyn OtherEffectFn>),
+    /// Give the status to the entity. Always hits.
+    Inflict(status::Status),
 }
 
 impl Debug for Effect {
@@ -48,10 +48,24 @@ impl Debug for Effect {
             Self::Other(_) => {
                 write!(f, "Effect::Other")
             }
+            Self::Inflict(st) => {
+                write!(f, "Effect::Inflict({st})")
+            }
         }
     }
 }
 
+/// Returns the lowest chance of any damage in the effects hitting, or 1 if there is none.
+pub fn effects_acc(effects: &[Effect]) -> f64 {
+    effects
+        .iter()
+        .filter_map(|ef| match ef {
+            Effect::DoDmg(inst) => Some(inst.acc),
+            _ => None,
+        })
+        .fold(1.0, f64::min)
+}
+
 #[derive(Clone, Debug)]
 pub struct MeleeAtk {
     /// Effect of the attack. Applied to each postion.
@@ -129,6 +143,11 @@ impl MeleeAtk {
         self.place.iter().any(|p| (*p + from) == target)
     }
 
+    /// Returns the chance of the attack hitting, before any modifiers.
+    pub fn acc(&self) -> f64 {
+        effects_acc(&self.effects)
+    }
+
     /// Return all the positions that the attack can hit with the damage that would be dealt.
     pub fn damage_map(&self, from: Point) -> HashMap<Point, i32> {
         let mut map = HashMap::new();
@@ -151,6 +170,8 @@ type CalcLineFx = fn(bool, Vec<Point>) -> Vec<(Point, Vfx)>;
 
 #[derive(Clone, Debug)]
 pub struct RangedAtk {
+    /// Name shown to the player when aiming it.
+    pub name: String,
     /// Damage dealt.
     pub effects: Vec<Effect>,
     /// Maximum distance from which the attack can be used.
@@ -159,23 +180,67 @@ pub struct RangedAtk {
     pub fx: Vec<(Point, Vfx)>,
     /// Closure used to convert a given line into some visual effects.
     pub line_fx: Box<CalcLineFx>,
+    /// If true, the attack hits everything along the line through the target until something
+    /// blocks it or it goes out of range, rather than just the target.
+    pub pierce: bool,
 }
 
 impl RangedAtk {
     /// Create a new ranged attack.
     pub fn new(
+        name: &str,
         effects: Vec<Effect>,
         range: u32,
         fx: Vec<(Point, Vfx)>,
         line_fx: Box<CalcLineFx>,
     ) -> Self {
         Self {
+            name: String::from(name),
             effects,
             range,
             fx,
             line_fx,
+            pierce: false,
         }
     }
+
+    /// Returns the chance of the attack hitting, before any modifiers.
+    pub fn acc(&self) -> f64 {
+        effects_acc(&self.effects)
+    }
+
+    /// Make the attack hit everything along its line, like a laser.
+    pub fn piercing(self) -> Self {
+        Self {
+            pierce: true,
+            ..self
+        }
+    }
+
+    /// Returns every position the attack goes through when fired from one position at another,
+    /// not including where it is fired from. Stops just before the first position where blocked
+    /// returns true.
+    pub fn path(&self, from: Point, target: Point, blocked: impl Fn(Point) -> bool) -> Vec<Point> {
+        let disp = target - from;
+        if !self.pierce || disp == Point::ORIGIN {
+            return vec![target];
+        }
+
+        // Carry the line on far enough past the target to cover the whole range.
+        let steps = disp.x.abs().max(disp.y.abs());
+        let far = from + disp * (self.range as i32).div_ceil(steps).max(1);
+
+        let mut path = Vec::new();
+        for p in Point::plot_line(from, far).skip(1).chain(std::iter::once(far)) {
+            if from.dist_squared(p) as u32 > self.range * self.range || blocked(p) {
+                break;
+            }
+            if path.last() != Some(&p) {
+                path.push(p);
+            }
+        }
+        path
+    }
 }
 
 /// Stores melee and ranged attacks. Associates the melee attacks with a direction.
@@ -210,6 +275,15 @@ impl AtkPat {
         map
     }
 
+    /// Returns the lowest chance of any melee attack hitting, before any modifiers.
+    pub fn melee_acc(&self) -> f64 {
+        self.melee_atks
+            .values()
+            .flatten()
+            .map(|atk| atk.acc())
+            .fold(1.0, f64::min)
+    }
+
     /// Create an attack pattern from the given melee attacks, which are assumed to have
     /// been created by [MeleeAtk::bulk_new]. Will panic if there are not 4 or 8 attacks in the
     /// vector.
@@ -261,6 +335,20 @@ impl AtkPat {
         possible
     }
 
+    /// Returns the index of the first attack in the given direction that hits any position for
+    /// which `is_target` returns true, when attacking from `from`.
+    pub fn first_hit_in(
+        &self,
+        from: Point,
+        dir: Point,
+        is_target: impl Fn(Point) -> bool,
+    ) -> Option<usize> {
+        self.melee_atks
+            .get(&dir)?
+            .iter()
+            .position(|atk| atk.place.iter().any(|p| is_target(*p + from)))
+    }
+
     // Returns the direction needed to hit the target from the given start position,
     // if there is one, as well as the index of the first attack in that direction
     // that hits the target.
@@ -276,3 +364,33 @@ impl AtkPat {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::style::Stylize;
+
+    fn atk(place: Vec<Point>) -> MeleeAtk {
+        MeleeAtk::new(Vec::new(), place, Vec::new(), Vfx::new_opaque('x'.stylize(), 1))
+    }
+
+    #[test]
+    fn first_hit_in() {
+        let dir = Point::new(1, 0);
+        let mut pat = AtkPat::empty();
+        pat.melee_atks.insert(
+            dir,
+            vec![
+                atk(vec![Point::new(1, 0)]),
+                atk(vec![Point::new(1, 1), Point::new(2, 0), Point::new(3, 0)]),
+            ],
+        );
+        let from = Point::new(5, 5);
+
+        assert_eq!(pat.first_hit_in(from, dir, |p| p == Point::new(6, 5)), Some(0));
+        // The index is of the attack, not of the position within it that hit.
+        assert_eq!(pat.first_hit_in(from, dir, |p| p == Point::new(8, 5)), Some(1));
+        assert_eq!(pat.first_hit_in(from, dir, |p| p == Point::new(9, 5)), None);
+        assert_eq!(pat.first_hit_in(from, Point::new(-1, 0), |_| true), None);
+    }
+}
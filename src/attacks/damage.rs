@@ -1,5 +1,7 @@
 //! Contains basic damage objects.
 
+use std::fmt;
+
 /// Some basic effect of an attack.
 #[derive(Clone, Copy, Debug)]
 pub enum DmgType {
@@ -22,6 +24,113 @@ impl DmgType {
     }
 }
 
+/// What caused some damage. Entities can resist or be weak to each kind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DmgKind {
+    /// Hit by a weapon.
+    Physical,
+    /// Caught in an explosion.
+    Explosive,
+    /// Burnt by a laser.
+    Laser,
+    /// Pushed into something.
+    Collision,
+}
+
+impl DmgKind {
+    /// Every kind of damage, in the order they are stored in resistances.
+    pub const ALL: [DmgKind; 4] = [
+        DmgKind::Physical,
+        DmgKind::Explosive,
+        DmgKind::Laser,
+        DmgKind::Collision,
+    ];
+}
+
+impl fmt::Display for DmgKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DmgKind::Physical => "physical",
+            DmgKind::Explosive => "explosive",
+            DmgKind::Laser => "laser",
+            DmgKind::Collision => "collision",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// How much an entity is affected by a kind of damage.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Resist {
+    /// Takes no damage.
+    Immune,
+    /// Takes 1 less damage.
+    Resistant,
+    /// Takes the normal amount of damage.
+    #[default]
+    Normal,
+    /// Takes 1 more damage.
+    Weak,
+}
+
+impl Resist {
+    /// Returns the damage taken instead of the given amount.
+    pub fn apply(self, dmg: u32) -> u32 {
+        match self {
+            Resist::Immune => 0,
+            Resist::Resistant => dmg.saturating_sub(1),
+            Resist::Normal => dmg,
+            Resist::Weak if dmg > 0 => dmg + 1,
+            Resist::Weak => dmg,
+        }
+    }
+}
+
+impl fmt::Display for Resist {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Resist::Immune => "Immune",
+            Resist::Resistant => "Resists",
+            Resist::Normal => "Normal",
+            Resist::Weak => "Weak",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// How much an entity is affected by each kind of damage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Resistances([Resist; DmgKind::ALL.len()]);
+
+impl Resistances {
+    /// Affected normally by everything.
+    pub const NONE: Resistances = Resistances([Resist::Normal; DmgKind::ALL.len()]);
+
+    /// Returns these resistances with the given kind of damage changed.
+    pub const fn with(mut self, kind: DmgKind, resist: Resist) -> Self {
+        self.0[kind as usize] = resist;
+        self
+    }
+
+    /// Returns how much the kind of damage affects the entity.
+    pub fn get(&self, kind: DmgKind) -> Resist {
+        self.0[kind as usize]
+    }
+
+    /// Returns the damage taken from the given amount of the kind of damage.
+    pub fn apply(&self, kind: DmgKind, dmg: u32) -> u32 {
+        self.get(kind).apply(dmg)
+    }
+
+    /// Returns every kind of damage that doesn't affect the entity normally.
+    pub fn unusual(&self) -> impl Iterator<Item = (DmgKind, Resist)> + '_ {
+        DmgKind::ALL
+            .into_iter()
+            .map(|kind| (kind, self.get(kind)))
+            .filter(|(_, r)| *r != Resist::Normal)
+    }
+}
+
 /// An instance of damage against a target.
 #[derive(Clone, Copy, Debug)]
 pub struct DmgInst {
@@ -29,6 +138,8 @@ pub struct DmgInst {
     pub dmg: DmgType,
     /// Chance of hitting from 0 to 1.
     pub acc: f64,
+    /// What causes the damage.
+    pub kind: DmgKind,
 }
 
 impl DmgInst {
@@ -37,14 +148,22 @@ impl DmgInst {
         Self {
             dmg: DmgType::Dmg(dmg),
             acc,
+            kind: DmgKind::Physical,
         }
     }
 
+    /// Returns this instance with the damage caused by something else.
+    pub fn of_kind(mut self, kind: DmgKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
     /// Create a damge instance that heals with 1.0 accuracy.
     pub fn heal(heal: u32) -> Self {
         Self {
             dmg: DmgType::Heal(heal),
             acc: 1.0,
+            kind: DmgKind::Physical,
         }
     }
 
@@ -53,6 +172,7 @@ impl DmgInst {
         Self {
             dmg: DmgType::Heal(heal),
             acc,
+            kind: DmgKind::Physical,
         }
     }
 
@@ -64,3 +184,30 @@ impl DmgInst {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resist_apply() {
+        assert_eq!(Resist::Immune.apply(3), 0);
+        assert_eq!(Resist::Resistant.apply(3), 2);
+        assert_eq!(Resist::Resistant.apply(0), 0);
+        assert_eq!(Resist::Normal.apply(3), 3);
+        assert_eq!(Resist::Weak.apply(3), 4);
+        // Weakness doesn't turn a miss into a hit.
+        assert_eq!(Resist::Weak.apply(0), 0);
+
+        let resist = Resistances::NONE
+            .with(DmgKind::Collision, Resist::Immune)
+            .with(DmgKind::Laser, Resist::Weak);
+        assert_eq!(resist.apply(DmgKind::Collision, 2), 0);
+        assert_eq!(resist.apply(DmgKind::Laser, 2), 3);
+        assert_eq!(resist.apply(DmgKind::Physical, 2), 2);
+        assert_eq!(
+            resist.unusual().collect::<Vec<_>>(),
+            [(DmgKind::Laser, Resist::Weak), (DmgKind::Collision, Resist::Immune)]
+        );
+    }
+}
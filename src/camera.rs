@@ -0,0 +1,171 @@
+//! Decides which part of the map is shown in the map view.
+
+use crate::{Point, REVEALED, entity::En, settings::SETTINGS};
+use crate::bn;
+use rect::Rect;
+
+/// Follows the player around the map, scrolling only when they get close to the edge of the view.
+#[derive(Clone, Debug)]
+pub struct Camera {
+    /// Tile in the middle of the view.
+    centre: Point,
+    /// Rooms of the current floor, used to find out how much of the floor has been revealed.
+    rooms: Vec<Rect>,
+    /// Whether the player has stood in each room of the current floor, for the fog modifier.
+    entered: Vec<bool>,
+    /// If true, centre the view on the room the player is in instead of following them.
+    pub room_focus: bool,
+}
+
+impl Camera {
+    /// Create a camera looking at the given position.
+    pub fn new(centre: Point) -> Self {
+        Self {
+            centre,
+            rooms: Vec::new(),
+            entered: Vec::new(),
+            room_focus: false,
+        }
+    }
+
+    /// Look at a new floor with the given rooms, starting at the given position.
+    pub fn set_floor(&mut self, rooms: Vec<Rect>, centre: Point) {
+        self.entered = vec![false; rooms.len()];
+        self.rooms = rooms;
+        self.centre = centre;
+    }
+
+    /// Return the tile in the middle of the view.
+    pub fn centre(&self) -> Point {
+        self.centre
+    }
+
+    /// Return the rooms of the current floor.
+    pub fn rooms(&self) -> &[Rect] {
+        &self.rooms
+    }
+
+    /// Returns true if the position is in a room the player has stood in on this floor.
+    pub fn entered(&self, pos: Point) -> bool {
+        self.rooms
+            .iter()
+            .zip(&self.entered)
+            .any(|(r, entered)| *entered && r.contains(pos))
+    }
+
+    /// Return the map position shown at the given offset from the top left of the view.
+    pub fn view_to_map(&self, off: Point, view_wid: u32, view_hgt: u32) -> Point {
+        let top_left = self.centre + Point::new(-(view_wid as i32 / 2), view_hgt as i32 / 2);
+        top_left + Point::new(off.x, -off.y)
+    }
+
+    /// Return the offset from the top left of the view that the given map position is shown at,
+    /// or None if it is outside of the view.
+    pub fn map_to_view(&self, pos: Point, view_wid: u32, view_hgt: u32) -> Option<Point> {
+        let top_left = self.centre + Point::new(-(view_wid as i32 / 2), view_hgt as i32 / 2);
+        let off = Point::new(pos.x - top_left.x, top_left.y - pos.y);
+
+        if off.x >= 0 && off.y >= 0 && off.x < view_wid as i32 && off.y < view_hgt as i32 {
+            Some(off)
+        } else {
+            None
+        }
+    }
+
+    /// Move the camera to keep the player in view, and return the top left corner of the view.
+    pub fn update(&mut self, map: &bn::Map<En>, player_pos: Point, view_wid: u32, view_hgt: u32) -> Point {
+        let half = Point::new(view_wid as i32 / 2, view_hgt as i32 / 2);
+
+        for (r, entered) in self.rooms.iter().zip(&mut self.entered) {
+            if r.contains(player_pos) {
+                *entered = true;
+            }
+        }
+
+        let room = if self.room_focus {
+            self.rooms
+                .iter()
+                .find(|r| r.contains(player_pos))
+                .filter(|r| r.wid <= view_wid as i32 && r.hgt <= view_hgt as i32)
+        } else {
+            None
+        };
+
+        if let Some(r) = room {
+            self.centre = room_centre(r);
+        } else {
+            // Only scroll once the player gets within the margin of an edge.
+            let margin = SETTINGS.camera_margin as i32;
+            let slack = Point::new(
+                (half.x - margin).max(0),
+                (half.y - margin).max(0),
+            );
+            self.centre = Point::new(
+                self.centre.x.clamp(player_pos.x - slack.x, player_pos.x + slack.x),
+                self.centre.y.clamp(player_pos.y - slack.y, player_pos.y + slack.y),
+            );
+
+            // Don't show the void past the parts of the floor that have been revealed.
+            if let Some((min, max)) = self.revealed_extents(map) {
+                self.centre = Point::new(
+                    clamp_axis(self.centre.x, min.x, max.x, half.x),
+                    clamp_axis(self.centre.y, min.y, max.y, half.y),
+                );
+            }
+        }
+
+        // The player must always be visible, whatever else happens.
+        self.centre = Point::new(
+            self.centre.x.clamp(player_pos.x - half.x, player_pos.x + half.x),
+            self.centre.y.clamp(player_pos.y - half.y, player_pos.y + half.y),
+        );
+
+        self.centre + Point::new(-half.x, half.y)
+    }
+
+    /// Returns the smallest and largest corners of the box around every revealed room, or None
+    /// if there are no rooms.
+    fn revealed_extents(&self, map: &bn::Map<En>) -> Option<(Point, Point)> {
+        let see_all = *REVEALED.read().unwrap();
+        let mut extents: Option<(Point, Point)> = None;
+
+        for r in &self.rooms {
+            if !see_all && !room_revealed(map, r) {
+                continue;
+            }
+
+            let low = Point::new(r.left, r.bottom());
+            let high = Point::new(r.right(), r.top);
+            extents = Some(match extents {
+                Some((min, max)) => (
+                    Point::new(min.x.min(low.x), min.y.min(low.y)),
+                    Point::new(max.x.max(high.x), max.y.max(high.y)),
+                ),
+                None => (low, high),
+            });
+        }
+
+        extents
+    }
+}
+
+/// Returns true if any of the room has been revealed. Only part of a room might have been seen,
+/// such as through a doorway, and the middle of an oddly shaped room might never be.
+pub fn room_revealed(map: &bn::Map<En>, r: &Rect) -> bool {
+    r.cells().any(|p| map.get_map(p).is_some_and(|t| t.revealed))
+}
+
+/// Returns the tile in the middle of the room.
+pub fn room_centre(r: &Rect) -> Point {
+    r.top_left() + Point::new(r.wid / 2, r.hgt / -2)
+}
+
+/// Clamp one axis of the centre of the view so that it stays within min and max, or centre it
+/// between them if they are closer together than the view is wide.
+fn clamp_axis(centre: i32, min: i32, max: i32, half: i32) -> i32 {
+    if max - min <= half * 2 {
+        (min + max) / 2
+    } else {
+        centre.clamp(min + half, max - half)
+    }
+}
@@ -0,0 +1,181 @@
+//! Classes the player can choose between before starting a run.
+
+use crate::{Point, bn, templates};
+use crate::attacks::{FOUR_POS_ATK, Resistances};
+use crate::entity::{En, EntityTemplate, PUZZLE};
+use crate::templates::{PLAYER_CHARACTER, PLAYER_COLOUR};
+use crossterm::style::{self, Stylize};
+use std::{fmt, sync::RwLock};
+
+/// Class chosen for the next run.
+pub static CLASS: RwLock<Class> = RwLock::new(Class::Fighter);
+
+/// A way of playing, with its own health, movement and attacks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Class {
+    /// The original player.
+    Fighter,
+    /// Lots of health and attacks that knock enemies back, but heals little between floors.
+    Brute,
+    /// Can leap two tiles at a time and heals more between floors, but has little health.
+    Skirmisher,
+    /// Can jump and attack like a chess knight.
+    Knight,
+    /// Can step and attack diagonally.
+    Duelist,
+    /// Can shoot enemies from a distance with a bow.
+    Archer,
+}
+
+impl Class {
+    /// Every class, in the order they are shown.
+    pub const ALL: [Class; 6] = [
+        Class::Fighter,
+        Class::Brute,
+        Class::Skirmisher,
+        Class::Knight,
+        Class::Duelist,
+        Class::Archer,
+    ];
+
+    /// Returns the template the player is made from when playing as this class.
+    pub fn template(self) -> EntityTemplate {
+        let manhattan = Point::ORIGIN.get_all_adjacent();
+        let default_atks = templates::get_default_atks(1, FOUR_POS_ATK, style::Color::Red);
+
+        let (max_hp, movement, atks) = match self {
+            Class::Fighter => (9, manhattan, default_atks),
+            Class::Brute => (
+                12,
+                manhattan,
+                templates::get_hvy_atks(1, FOUR_POS_ATK, style::Color::Red),
+            ),
+            Class::Skirmisher => {
+                let mut movement = manhattan;
+                movement.extend(templates::get_manhattan_n(2));
+                (7, movement, default_atks)
+            }
+            Class::Knight => {
+                let mut movement = manhattan;
+                movement.extend(templates::get_knight_moves());
+                let mut atks = default_atks;
+                atks.melee_atks.extend(templates::get_knight_atks().melee_atks);
+                (8, movement, atks)
+            }
+            Class::Duelist => {
+                let mut atks = default_atks;
+                atks.melee_atks.extend(
+                    templates::get_diag_atks(1, style::Color::Red, 1, 7, true).melee_atks,
+                );
+                (7, Point::ORIGIN.get_all_adjacent_diagonal(), atks)
+            }
+            Class::Archer => {
+                let mut atks = default_atks;
+                atks.ranged_atks.push(templates::get_bow(1, 5));
+                (7, manhattan, atks)
+            }
+        };
+
+        EntityTemplate {
+            max_hp,
+            actions: Vec::new(),
+            movement,
+            ch: PLAYER_CHARACTER.with(PLAYER_COLOUR),
+            atks,
+            resist: Resistances::NONE,
+        }
+    }
+
+    /// Returns the health restored after getting past a floor's boss.
+    pub fn floor_heal(self) -> u32 {
+        match self {
+            Class::Fighter | Class::Knight | Class::Duelist | Class::Archer => 2,
+            Class::Brute => 1,
+            Class::Skirmisher => 3,
+        }
+    }
+
+    /// Returns a short description of what makes the class different, for the class screen.
+    pub fn desc(self) -> &'static str {
+        match self {
+            Class::Fighter => "balanced",
+            Class::Brute => "knockback, heals 1",
+            Class::Skirmisher => "leaps, heals 3",
+            Class::Knight => "knight jumps and strikes",
+            Class::Duelist => "diagonal steps and strikes",
+            Class::Archer => "shoots with a bow",
+        }
+    }
+
+    /// Returns a two character tag for this class, used where there isn't much space.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Class::Fighter => "FI",
+            Class::Brute => "BR",
+            Class::Skirmisher => "SK",
+            Class::Knight => "KN",
+            Class::Duelist => "DU",
+            Class::Archer => "AR",
+        }
+    }
+
+    /// Returns the class with the given tag, if there is one.
+    pub fn from_tag(tag: &str) -> Option<Class> {
+        Self::ALL.into_iter().find(|c| c.tag() == tag)
+    }
+}
+
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Class::Fighter => "Fighter",
+            Class::Brute => "Brute",
+            Class::Skirmisher => "Skirmisher",
+            Class::Knight => "Knight",
+            Class::Duelist => "Duelist",
+            Class::Archer => "Archer",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Returns the class being played. Puzzles are always played as a fighter.
+pub fn current() -> Class {
+    if unsafe { PUZZLE.is_some() } {
+        Class::Fighter
+    } else {
+        *CLASS.read().unwrap()
+    }
+}
+
+/// Creates the player entity for the class being played.
+pub fn get_player() -> En {
+    En::from_template(&current().template(), true, false)
+}
+
+/// Returns the displacement of a move that starts in the first direction and then goes in the
+/// second, if the player's movement allows it. Going in the same direction twice leaps two tiles,
+/// and turning steps diagonally or, failing that, makes a knight's jump. Every tile passed over
+/// on the way, going in the first direction before turning, can't block movement.
+pub fn special_move(
+    map: &bn::Map<En>,
+    pl: &En,
+    pos: Point,
+    first: Point,
+    second: Point,
+) -> Option<Point> {
+    let path = if first == second {
+        vec![first]
+    } else if first + second == Point::ORIGIN {
+        return None;
+    } else if pl.movement.contains(&(first + second)) {
+        vec![first]
+    } else {
+        vec![first, first * 2]
+    };
+    let disp = *path.last().unwrap() + second;
+
+    (pl.movement.contains(&disp)
+        && path.iter().all(|p| map.get_map(pos + *p).is_some_and(|t| !t.blocking)))
+    .then_some(disp)
+}
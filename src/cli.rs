@@ -0,0 +1,261 @@
+//! Commands that can be run from the command line instead of playing, for looking into the floors
+//! the generator makes.
+
+use crate::entity::{En, Special};
+use crate::glyphs::{ASCII_ENV_VAR, glyphs};
+use crate::items::Item;
+use crate::map_gen::bandit_gen::{FloorInfo, gen_run};
+use crate::map_gen::validate;
+use crate::modifiers::{MODIFIERS, Modifiers};
+use crate::templates::PLAYER_CHARACTER;
+use crate::tile_presets::{OPEN_GATE_CHAR, PLATE_CHAR, SPIKE_CHAR};
+use crate::{DOOR_CHAR, ICE_CHAR, KILL_SCREEN, Point, REVEALED, bn, templates};
+use crossterm::style::Stylize;
+use std::collections::HashSet;
+
+/// Seeds checked by the validate command if it isn't told how many.
+const DEFAULT_SEEDS: u64 = 1024;
+
+const USAGE: &str = "\
+usage: untitled_bandit [command]
+
+The game is played if no command is given.
+
+commands:
+    validate <seed> [count]   check every floor of count seeds (1024 by default), starting at seed
+    inspect <seed> [floor]    print the floor (0 by default) generated from seed, with its rooms,
+                              keys, exit and enemies
+    help                      show this message
+
+Seeds are written in hex, as shown in game. Run codes can be used too, to use their modifiers.
+Floors are printed in ascii unless UNTITLED_BANDIT_ASCII is set to 0.";
+
+/// Turn a seed or run code into a seed, as the seeded run menu does. Run codes also turn on the
+/// modifiers they were played with.
+fn parse_seed(txt: &str) -> Result<u64, String> {
+    if let Some((seed, mods)) = Modifiers::from_run_code(txt) {
+        *MODIFIERS.write().unwrap() = mods;
+        Ok(seed)
+    } else {
+        u64::from_str_radix(txt, 16).map_err(|_| format!("'{txt}' is not a seed"))
+    }
+}
+
+/// Check a range of seeds, printing every problem found. Returns true if there were none.
+fn validate(args: &[String]) -> Result<bool, String> {
+    let init_seed = parse_seed(args.first().ok_or("no seed given")?)?;
+    let sds = match args.get(1) {
+        Some(txt) => txt.parse().map_err(|_| format!("'{txt}' is not a number of seeds"))?,
+        None => DEFAULT_SEEDS,
+    };
+
+    let meta = templates::metadata::get_metadata();
+    let (templates, elites) = templates::get_templates();
+    let mut found = 0;
+
+    for sd in init_seed..init_seed.saturating_add(sds) {
+        eprint!("Trying {sd:X}\r");
+        for report in validate::check_seed(sd, &meta, &templates, &elites) {
+            println!("{report}");
+            found += 1;
+        }
+    }
+    eprintln!("Checked {sds} seeds, found {found} problems");
+
+    Ok(found == 0)
+}
+
+/// Returns each character a tile can be drawn with and what it is, for the legend of a floor.
+fn tile_legend() -> Vec<(char, String)> {
+    let g = glyphs();
+    let mut legend: Vec<(char, String)> = [
+        (PLAYER_CHARACTER, "player"),
+        ('#', "wall"),
+        ('.', "floor"),
+        (DOOR_CHAR, "door"),
+        (g.locked_door, "locked door"),
+        (g.wall_sentry, "wall sentry"),
+        (g.key, "key"),
+        ('>', "exit"),
+        (ICE_CHAR, "ice"),
+        (SPIKE_CHAR, "spikes"),
+        (PLATE_CHAR, "pressure plate"),
+        (g.gate, "gate"),
+        (OPEN_GATE_CHAR, "open gate"),
+        (g.teleporter, "teleporter"),
+        (g.cracked_wall, "cracked wall"),
+        (g.boss, "boss"),
+    ]
+    .into_iter()
+    .map(|(ch, name)| (ch, name.to_string()))
+    .collect();
+    for (ch, dir) in g.arrows.into_iter().zip(["down", "left", "up", "right"]) {
+        legend.push((ch, format!("conveyor pointing {dir}")));
+    }
+    for it in Item::ALL {
+        legend.push((*it.ch().content(), it.to_string()));
+    }
+    legend
+}
+
+/// Returns the floor drawn as text with a legend of the characters in it, followed by its rooms,
+/// keys, exit and the enemies in each room.
+fn describe(seed: u64, floor: u32, map: &bn::Map<En>, info: &FloorInfo) -> String {
+    let mut out = format!("Seed {seed:X}, floor {floor} ({})\n", info.theme);
+    let Some(first) = info.rooms.first() else {
+        return out;
+    };
+
+    // Corners of the box around every room.
+    let mut min = Point::new(first.left, first.bottom());
+    let mut max = Point::new(first.right(), first.top);
+    for r in &info.rooms {
+        min = Point::new(min.x.min(r.left), min.y.min(r.bottom()));
+        max = Point::new(max.x.max(r.right()), max.y.max(r.top));
+    }
+
+    out.push('\n');
+    let mut drawn = HashSet::new();
+    let mut enemies = Vec::new();
+    for y in (min.y..=max.y).rev() {
+        let mut line = String::new();
+        for x in min.x..=max.x {
+            let p = Point::new(x, y);
+            let ch = match (map.get_ent(p), map.get_map(p)) {
+                (Some(e), _) => bn::Entity::repr(e),
+                (None, Some(t)) => bn::Tile::repr(t),
+                (None, None) => ' '.stylize(),
+            };
+            let ch = *ch.content();
+            let enemy = map.get_ent(p).is_some_and(|e| {
+                !e.is_player && !matches!(e.special, Special::WallSentry | Special::FinalBoss)
+            });
+            if !enemy {
+                drawn.insert(ch);
+            } else if !enemies.contains(&ch) {
+                enemies.push(ch);
+            }
+            line.push(ch);
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+
+    // Only the characters that were drawn are explained.
+    out.push_str("\nLegend:\n");
+    for (ch, name) in tile_legend().into_iter().filter(|(ch, _)| drawn.contains(ch)) {
+        out.push_str(&format!("  {ch} {name}\n"));
+    }
+    if !enemies.is_empty() {
+        enemies.sort();
+        let chars: Vec<String> = enemies.iter().map(char::to_string).collect();
+        out.push_str(&format!("  {} enemies, counted in each room below\n", chars.join(" ")));
+    }
+
+    out.push_str("\nRooms:\n");
+    for (id, rm) in info.rooms.iter().enumerate() {
+        let mut tags = Vec::new();
+        if id == 0 {
+            tags.push("start");
+        }
+        if info.exit.is_some_and(|p| rm.contains(p)) {
+            tags.push("exit");
+        }
+        if info.keys.iter().any(|(p, _)| rm.contains(*p)) {
+            tags.push("key");
+        }
+
+        // Count each kind of enemy in the room.
+        let mut roster: Vec<(char, u32)> = Vec::new();
+        for e in rm.inner_cells().filter_map(|p| map.get_ent(p)).filter(|e| !e.is_player) {
+            let ch = *e.ch.content();
+            match roster.iter_mut().find(|(c, _)| *c == ch) {
+                Some((_, n)) => *n += 1,
+                None => roster.push((ch, 1)),
+            }
+        }
+        roster.sort();
+        let roster = if roster.is_empty() {
+            String::from("no enemies")
+        } else {
+            roster
+                .iter()
+                .map(|(ch, n)| if *n == 1 { ch.to_string() } else { format!("{ch} x{n}") })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let tags = if tags.is_empty() { String::new() } else { format!(" [{}]", tags.join(", ")) };
+        out.push_str(&format!(
+            "  {id}: {}x{} at {}{tags}: {roster}\n",
+            rm.wid,
+            rm.hgt,
+            rm.top_left(),
+        ));
+    }
+
+    let keys: Vec<String> = info.keys.iter().map(|(p, id)| format!("{p} (id {id})")).collect();
+    out.push_str(&format!(
+        "Keys: {}\n",
+        if keys.is_empty() { String::from("none") } else { keys.join(", ") }
+    ));
+    out.push_str(&format!(
+        "Exit: {}\n",
+        info.exit.map_or(String::from("none"), |p| p.to_string())
+    ));
+
+    out
+}
+
+/// Generate a seed's floors up to the one asked for, and print that one.
+fn inspect(args: &[String]) -> Result<bool, String> {
+    let seed = parse_seed(args.first().ok_or("no seed given")?)?;
+    let floor = match args.get(1) {
+        Some(txt) => txt
+            .parse::<u32>()
+            .ok()
+            .filter(|f| *f < KILL_SCREEN as u32)
+            .ok_or_else(|| format!("'{txt}' is not a floor"))?,
+        None => 0,
+    };
+
+    // Nothing else is running yet, and the glyphs haven't been picked.
+    if std::env::var_os(ASCII_ENV_VAR).is_none() {
+        unsafe { std::env::set_var(ASCII_ENV_VAR, "1") };
+    }
+    // Show everything, not just what the player would have seen.
+    *REVEALED.write().unwrap() = true;
+
+    let meta = templates::metadata::get_metadata();
+    let (templates, elites) = templates::get_templates();
+    gen_run(seed, floor, &meta, &templates, &elites, |f, map, info| {
+        if f == floor {
+            print!("{}", describe(seed, f, map, info));
+        }
+    });
+
+    Ok(true)
+}
+
+/// Run the command given by the arguments, not counting the name of the program. Returns the
+/// exit code.
+pub fn run(args: &[String]) -> i32 {
+    let res = match args.first().map(String::as_str) {
+        Some("validate") => validate(&args[1..]),
+        Some("inspect") => inspect(&args[1..]),
+        Some("help" | "-h" | "--help") => {
+            println!("{USAGE}");
+            Ok(true)
+        }
+        _ => Err(format!("unknown command\n\n{USAGE}")),
+    };
+
+    match res {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(e) => {
+            eprintln!("{e}");
+            2
+        }
+    }
+}
@@ -13,6 +13,7 @@ use std::fmt;
 use std::rc::Rc;
 use std::sync::{LazyLock, RwLock};
 use map_gen::bandit_gen::MAX_WIDTH;
+use status::{Status, StatusKind};
 
 /// Type of action the player will perform.
 pub static mut ACTION: ActionType = ActionType::Wait;
@@ -30,8 +31,17 @@ pub static mut GLOBAL_TIME: u32 = 0;
 pub static mut COMBAT_TIME: u32 = 0;
 /// Points of damage dealt to enemies.
 pub static mut DAMAGE_DEALT: u32 = 0;
+/// Number of attacks by the player that hit an enemy over the course of the run.
+pub static mut HITS: u32 = 0;
+/// Number of attacks by the player that missed an enemy over the course of the run.
+pub static mut MISSES: u32 = 0;
 /// Number of floors cleared.
 pub static mut FLOORS_CLEARED: u32 = 0;
+/// Number of floors descended. The same as FLOORS_CLEARED, except in endless mode where it keeps
+/// counting after FLOORS_CLEARED goes back to the start.
+pub static mut DEPTH: u32 = 0;
+/// Whether the run keeps going forever instead of ending after the last floor.
+pub static mut ENDLESS: bool = false;
 /// True when the floor should be regenerated.
 pub static mut NEXT_FLOOR: bool = false;
 /// List of all keys the player has collected.
@@ -67,42 +77,199 @@ pub const KEY_CLRS: [style::Color; 4] = [
     style::Color::Blue,
 ];
 pub const KEY_CLRS_COUNT: usize = KEY_CLRS.len();
-const WALL_SENTRY_CHAR: char = '█';
+
+/// Something that has happened, to be shown in the log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LogEvent {
+    /// An attack that did damage.
+    Damage { from: char, to: char, amount: u32, kind: DmgKind },
+    /// An attack that healed.
+    Heal { from: char, to: char, amount: u32 },
+    /// The health of an entity changed.
+    HpChange { who: char, old: u32, new: u32, max: u32 },
+    /// An entity was pushed into something.
+    Collision { who: char, with: char },
+    /// An attack missed.
+    Miss { from: char, to: char },
+    /// An enemy died.
+    Death(char),
+    /// The player picked up a key of the given type.
+    KeyGained(u32),
+    /// The player unlocked a door of the given type.
+    DoorUnlocked(u32),
+    /// The player arrived on a floor, named by the given text.
+    FloorEntered(String),
+    /// An enemy summoned another.
+    Summon { by: char, summoned: char },
+    /// The player stopped travelling because something happened.
+    TravelStopped,
+    /// The player picked up an item.
+    ItemGained(items::Item),
+    /// The player used an item.
+    ItemUsed(items::Item),
+    /// The player took an upgrade.
+    Upgraded(upgrades::Upgrade),
+    /// An entity was given a status.
+    StatusGained { who: char, kind: StatusKind },
+    /// A status damaged an entity.
+    StatusDamage { who: char, kind: StatusKind, amount: u32 },
+    /// A shield stopped a hit.
+    Absorbed(char),
+    /// Anything else.
+    Other(String),
+}
+
+impl LogEvent {
+    /// Returns the colour the event is shown in.
+    pub fn clr(&self) -> style::Color {
+        let pl = templates::PLAYER_CHARACTER;
+        match self {
+            Self::Damage { to, .. } if *to == pl => style::Color::Red,
+            Self::Damage { .. } => style::Color::White,
+            Self::Heal { .. } => style::Color::Green,
+            Self::HpChange { old, new, .. } if new > old => style::Color::Green,
+            Self::HpChange { who, .. } if *who == pl => style::Color::Red,
+            Self::HpChange { .. } => style::Color::Grey,
+            Self::Collision { who, .. } if *who == pl => style::Color::Red,
+            Self::Collision { .. } => style::Color::White,
+            Self::Miss { .. } => style::Color::DarkGrey,
+            Self::Death(_) => style::Color::DarkYellow,
+            Self::KeyGained(k) | Self::DoorUnlocked(k) => KEY_CLRS[*k as usize % KEY_CLRS.len()],
+            Self::FloorEntered(_) => style::Color::Cyan,
+            Self::Summon { .. } => style::Color::Magenta,
+            Self::TravelStopped => style::Color::DarkGrey,
+            Self::ItemGained(item) | Self::ItemUsed(item) => item.clr(),
+            Self::Upgraded(upgrade) => upgrade.clr(),
+            Self::StatusGained { kind, .. } | Self::StatusDamage { kind, .. } => kind.clr(),
+            Self::Absorbed(_) => StatusKind::Shield.clr(),
+            Self::Other(_) => style::Color::White,
+        }
+    }
+
+    /// Returns what hurt the entity shown with the given character in this event, if anything.
+    pub fn harm_to(&self, who: char) -> Option<String> {
+        match self {
+            Self::Damage { from, to, amount, kind } if *to == who && *amount > 0 => {
+                Some(format!("{from} ({kind})"))
+            }
+            Self::Collision { who: hurt, with } if *hurt == who => {
+                Some(format!("{with} ({})", DmgKind::Collision))
+            }
+            Self::StatusDamage { who: hurt, kind, .. } if *hurt == who => {
+                Some(kind.to_string().to_lowercase())
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for LogEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pl = templates::PLAYER_CHARACTER;
+        match self {
+            Self::Damage { from, to, amount, kind } => write!(f, "{from} {amount} {kind} -> {to}"),
+            Self::Heal { from, to, amount } => write!(f, "{from} -{amount} -> {to}"),
+            Self::HpChange { who, old, new, max } => write!(f, "{who} hp: {old}/{max}->{new}/{max}"),
+            Self::Collision { who, with } => write!(f, "{who} collides with {with}"),
+            Self::Miss { from, to } => write!(f, "{from} misses {to}"),
+            Self::Death(who) => write!(f, "{who} is dead"),
+            Self::KeyGained(_) => write!(f, "{pl} gains key"),
+            Self::DoorUnlocked(_) => write!(f, "{pl} unlocks door"),
+            Self::FloorEntered(flr) => write!(f, "{pl} enters floor {flr}"),
+            Self::Summon { by, summoned } => write!(f, "{by} summons {summoned}"),
+            Self::TravelStopped => write!(f, "{pl} stops travelling"),
+            Self::ItemGained(item) => write!(f, "{pl} gains {item}"),
+            Self::ItemUsed(item) => write!(f, "{pl} uses {item}"),
+            Self::Upgraded(upgrade) => write!(f, "{pl} takes {upgrade}"),
+            Self::StatusGained { who, kind } => write!(f, "{who} is {}", kind.adjective()),
+            Self::StatusDamage { who, kind, amount } => {
+                write!(f, "{who} takes {amount} from {}", kind.to_string().to_lowercase())
+            }
+            Self::Absorbed(who) => write!(f, "{who}'s shield absorbs a hit"),
+            Self::Other(txt) => write!(f, "{txt}"),
+        }
+    }
+}
 
 /// Displays a log message.
 #[derive(Clone)]
 pub struct LogMsg {
-    txt: String,
+    event: LogEvent,
     t_stamp: u32,
+    floor: u32,
 }
 
 impl LogMsg {
-    /// Create a new message using GLOBAL_TIME and the given text.
-    pub fn new(txt: String) -> Self {
+    /// Create a new message about the given event using GLOBAL_TIME and DEPTH.
+    pub fn new(event: LogEvent) -> Self {
         Self {
-            txt,
+            event,
             t_stamp: unsafe { GLOBAL_TIME },
+            floor: unsafe { DEPTH },
         }
     }
 
     /// Create a message that informs of a change in health.
     pub fn hp_change(old: u32, datum: &Datum<u32>, target: char) -> Self {
-        Self::new(format!("{target} hp: {old}/{}->{}/{}", datum.max, *datum, datum.max))
+        Self::new(LogEvent::HpChange {
+            who: target,
+            old,
+            new: *datum.value(),
+            max: datum.max,
+        })
+    }
+
+    /// Return what happened.
+    pub fn event(&self) -> &LogEvent {
+        &self.event
+    }
+
+    /// Return the floor the message was created on.
+    pub fn floor(&self) -> u32 {
+        self.floor
+    }
+
+    /// Return the colour the message is shown in.
+    pub fn clr(&self) -> style::Color {
+        self.event.clr()
     }
 }
 
 impl fmt::Display for LogMsg {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "T+{}: {}", self.t_stamp, self.txt)
+        write!(f, "T+{}: {}", self.t_stamp, self.event)
+    }
+}
+
+impl From<LogEvent> for LogMsg {
+    fn from(val: LogEvent) -> Self {
+        Self::new(val)
     }
 }
 
 impl From<String> for LogMsg {
     fn from(val: String) -> Self {
-        Self::new(val)
+        Self::new(LogEvent::Other(val))
     }
 }
 
+/// Returns a command that gives the status to the entity at the target, logging it.
+pub fn inflict(target: Point, st: Status) -> bn::Cmd<En> {
+    bn::Cmd::new_on(target).modify_entity(Box::new(move |e: &mut En| {
+        if e.special == Special::WallSentry {
+            return;
+        }
+        e.add_status(st);
+        LOG_MSGS.write().unwrap().push(
+            LogEvent::StatusGained {
+                who: *e.ch.content(),
+                kind: st.kind,
+            }
+            .into(),
+        );
+    }))
+}
+
 /// Describes the way in which an entity differs from a normal entity.
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum Special {
@@ -118,6 +285,20 @@ pub enum Special {
     Not,
 }
 
+impl fmt::Display for Special {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let txt = match self {
+            Self::WallSentry => "Wall sentry",
+            Self::Missile => "Missile",
+            Self::Minion => "Minion",
+            Self::FinalBoss => "Final boss",
+            Self::Not => "Normal",
+        };
+
+        write!(f, "{txt}")
+    }
+}
+
 /// A template for creating an entity from.
 #[derive(Clone, Debug)]
 pub struct EntityTemplate {
@@ -126,6 +307,7 @@ pub struct EntityTemplate {
     pub movement: Vec<Point>,
     pub ch: style::StyledContent<char>,
     pub atks: AtkPat,
+    pub resist: Resistances,
 }
 
 #[derive(Clone)]
@@ -152,6 +334,12 @@ pub struct En {
     pub acted: bool,
     /// Contains a value if the entity is forced to move in a specific direction.
     pub vel: Option<Point>,
+    /// Statuses currently affecting the entity, at most one of each kind.
+    pub statuses: Vec<Status>,
+    /// How much each kind of damage affects the entity.
+    pub resist: Resistances,
+    /// Was the entity made from an elite template? Elites leave an item behind when they die.
+    pub elite: bool,
 }
 
 impl En {
@@ -178,6 +366,9 @@ impl En {
             dormant,
             acted: false,
             vel: None,
+            statuses: Vec::new(),
+            resist: Resistances::NONE,
+            elite: false,
         }
     }
 
@@ -189,6 +380,7 @@ impl En {
             movement,
             ch,
             atks,
+            resist,
         } = template.clone();
 
         Self::new(
@@ -201,11 +393,54 @@ impl En {
             atks,
             dormant,
         )
+        .with_resist(resist)
+    }
+
+    /// Returns the entity with the given resistances.
+    pub fn with_resist(mut self, resist: Resistances) -> Self {
+        self.resist = resist;
+        self
     }
 
     /// Applies the given given damage instance to this entity. Returns whether
     /// or not it is still alive.
     pub fn apply_dmg(&mut self, dmg: DmgInst) -> bool {
+        match dmg.dmg {
+            DmgType::Heal(h) => {
+                self.hp += h;
+                false
+            }
+            DmgType::Dmg(d) => {
+                let d = self.resist.apply(dmg.kind, d);
+                // Being hit breaks the ice.
+                self.statuses.retain(|s| s.kind != StatusKind::Frozen);
+
+                if d > 0
+                    && let Some(shield) = self.statuses.iter_mut().find(|s| s.kind == StatusKind::Shield)
+                {
+                    shield.amount -= 1;
+                    self.statuses.retain(|s| s.amount > 0);
+                    LOG_MSGS.write().unwrap().push(LogEvent::Absorbed(*self.ch.content()).into());
+                    return false;
+                }
+
+                self.take_dmg(d)
+            }
+        }
+    }
+
+    /// Returns how much health the damage instance would take away, after resistances. Negative
+    /// for heals.
+    pub fn dmg_taken(&self, dmg: DmgInst) -> i32 {
+        match dmg.dmg {
+            DmgType::Heal(h) => -(h as i32),
+            DmgType::Dmg(_) if self.status(StatusKind::Shield).is_some() => 0,
+            DmgType::Dmg(d) => self.resist.apply(dmg.kind, d) as i32,
+        }
+    }
+
+    /// Lose the given amount of health, ignoring any shield. Returns true if this kills it.
+    fn take_dmg(&mut self, d: u32) -> bool {
         let can_count = if !self.is_player
             && let Special::Not = self.special
         {
@@ -213,30 +448,23 @@ impl En {
         } else {
             false
         };
-        match dmg.dmg {
-            DmgType::Heal(h) => {
-                self.hp += h;
-                false
+
+        if d > *self.hp {
+            if can_count {
+                unsafe {
+                    DAMAGE_DEALT += *self.hp;
+                }
             }
-            DmgType::Dmg(d) => {
-                if d > *self.hp {
-                    if can_count {
-                        unsafe {
-                            DAMAGE_DEALT += *self.hp;
-                        }
-                    }
-                    self.hp.set_to(0);
-                    true
-                } else {
-                    if can_count {
-                        unsafe {
-                            DAMAGE_DEALT += d;
-                        }
-                    }
-                    self.hp -= d;
-                    false
+            self.hp.set_to(0);
+            true
+        } else {
+            if can_count {
+                unsafe {
+                    DAMAGE_DEALT += d;
                 }
             }
+            self.hp -= d;
+            false
         }
     }
 
@@ -244,6 +472,50 @@ impl En {
     pub fn is_dead(&self) -> bool {
         self.hp == 0
     }
+
+    /// Returns the status of the given kind, if the entity has one.
+    pub fn status(&self, kind: StatusKind) -> Option<&Status> {
+        self.statuses.iter().find(|s| s.kind == kind)
+    }
+
+    /// Give the entity a status. If it already has one of the same kind, it keeps whichever
+    /// lasts longer.
+    pub fn add_status(&mut self, st: Status) {
+        match self.statuses.iter_mut().find(|s| s.kind == st.kind) {
+            Some(cur) => cur.amount = cur.amount.max(st.amount),
+            None => self.statuses.push(st),
+        }
+    }
+
+    /// Returns true if a status stops the entity acting on its next turn.
+    pub fn held(&self) -> bool {
+        self.statuses.iter().any(|s| s.holds())
+    }
+
+    /// Apply poison and make timed statuses wear off by a turn.
+    fn tick_statuses(&mut self) {
+        if self.status(StatusKind::Poison).is_some() {
+            let old = *self.hp;
+            self.take_dmg(status::POISON_DMG);
+
+            let mut handle = LOG_MSGS.write().unwrap();
+            let who = *self.ch.content();
+            handle.push(
+                LogEvent::StatusDamage {
+                    who,
+                    kind: StatusKind::Poison,
+                    amount: status::POISON_DMG,
+                }
+                .into(),
+            );
+            handle.push(LogMsg::hp_change(old, &self.hp, who));
+        }
+
+        for st in self.statuses.iter_mut().filter(|s| s.timed()) {
+            st.amount -= 1;
+        }
+        self.statuses.retain(|s| s.amount > 0);
+    }
 }
 
 impl fmt::Display for En {
@@ -258,19 +530,27 @@ impl bn::Entity for En {
 
     fn repr(&self) -> <<Self as Entity>::Tile as bn::Tile>::Repr {
         if !self.dormant || *REVEALED.read().unwrap() {
-            // Required as the player has no action queue.
-            if self.is_player || self.special == Special::Missile {
-                return self.ch;
-            }
-            
-            if *SEE_HEALTH.read().unwrap() && self.special != Special::WallSentry {
-                return char::from_digit(*self.hp, 16).unwrap().red();
-            }
-            
-            // Highlight if about to act.
-            match self.actions.get(self.count).unwrap() {
-                ActionType::Wait | ActionType::Pathfind | ActionType::Flee(_) => self.ch,
-                _ => self.ch.on_red(),
+            let ch = if self.is_player || self.special == Special::Missile {
+                // Required as the player has no action queue.
+                self.ch
+            } else if *SEE_HEALTH.read().unwrap() && self.special != Special::WallSentry {
+                char::from_digit(*self.hp, 16).unwrap().red()
+            } else if self.held() {
+                // Not going to act, whatever is next.
+                self.ch
+            } else {
+                // Highlight if about to act.
+                match self.actions.get(self.count).unwrap() {
+                    ActionType::Wait | ActionType::Pathfind | ActionType::Flee(_) => self.ch,
+                    _ => self.ch.on_red(),
+                }
+            };
+
+            // Underline anything with a status.
+            if self.statuses.is_empty() {
+                ch
+            } else {
+                ch.underlined()
             }
         } else {
             ' '.stylize()
@@ -292,7 +572,22 @@ impl bn::Entity for En {
                         unsafe { DEAD = true }
                     } else {
                         let mut handle = LOG_MSGS.write().unwrap();
-                        handle.push(format!("{} is dead", *self.ch.content()).into());
+                        handle.push(LogEvent::Death(*self.ch.content()).into());
+
+                        // Elites leave an item behind, as long as they died on plain floor.
+                        if self.special == Special::Not && self.elite && unsafe { PUZZLE.is_none() } {
+                            let item = items::Item::ALL[rand::random_range(0..items::Item::ALL.len())];
+                            cmd.queue(bn::Cmd::new_on(pos).modify_tile(Box::new(move |t: &mut Tile| {
+                                if !t.door
+                                    && !t.blocking
+                                    && t.step_effect.is_none()
+                                    && !t.slippery
+                                    && t.mechanism.is_none()
+                                {
+                                    *t = tile_presets::get_item(true, item);
+                                }
+                            })));
+                        }
                         unsafe {
                             if self.special != Special::Minion {
                                 KILLED += 1;
@@ -314,7 +609,7 @@ impl bn::Entity for En {
                     if self.special == Special::FinalBoss {
                         let mut rm_rect = rect::Rect::new(-MAX_WIDTH / 2, MAX_WIDTH / 2, MAX_WIDTH, MAX_WIDTH);
                         // Put the player in the middle.
-                        cmd.queue(bn::Cmd::new_on(unsafe { PLAYER }).move_to(Point::ORIGIN));
+                        cmd.queue_many(tile_presets::force_move(&*cmd, unsafe { PLAYER }, Point::ORIGIN));
 
                         for i in 1..=MAX_WIDTH / 2 {
                             for p in rm_rect.edges() {
@@ -349,6 +644,8 @@ impl bn::Entity for En {
             }
 
             if !self.is_player {
+                // Don't hold a gate open from beyond the grave.
+                cmd.queue_many(tile_presets::leave(&*cmd, pos, None));
                 cmd.queue(bn::Cmd::new_here().delete_entity());
             }
             return;
@@ -372,24 +669,17 @@ impl bn::Entity for En {
             _ => (),
         }
 
+        // Statuses wear off, and might stop us acting this turn.
+        let held = self.held();
+        if !self.statuses.is_empty() {
+            cmd.queue(bn::Cmd::new_here().modify_entity(Box::new(|e: &mut En| e.tick_statuses())));
+        }
+
         let mut acted = false;
         let mut new_count = self.count + 1;
 
         // Check there is nothing at the given pos.
-        let verify_pos = |cmd: &bn::Commands<'_, Self>, pos: Point| match cmd.get_map(pos) {
-            Some(t) => match cmd.get_ent(pos) {
-                Some(_e) => false,
-                None => !t.blocking,
-            },
-            None => false,
-        };
-
-        // Checks there is nothing blocking between the two points.
-        let verify_line = |cmd: &bn::Commands<'_, Self>, from: Point, to: Point| {
-            Point::plot_line(from, to)
-                .skip(1)
-                .all(|p| verify_pos(cmd, p))
-        };
+        let verify_pos = |cmd: &bn::Commands<'_, Self>, pos: Point| targeting::clear(cmd, pos);
 
         // Return the closest entity to the given position that is visible and within
         // range, if there is one.
@@ -398,7 +688,7 @@ impl bn::Entity for En {
                 .filter(move |(p, e)| {
                     e.is_player == friendly
                         && **p != to
-                        && verify_line(cmd, to, **p)
+                        && targeting::line_clear(cmd, to, **p)
                         && to.dist_squared(**p) as u32 <= range * range
                 })
                 .min_by_key(move |(p, _e)| to.dist_squared(**p))
@@ -434,6 +724,9 @@ impl bn::Entity for En {
             best
         };
 
+        // Where we started the turn, to tell if we have moved before attacking.
+        let start = pos;
+
         // Perform a melee attack in the given direction, unless a ranged
         // attack is specified. In that case, the ranged attack at the provided index occurs.
         let do_attack = |pos: Point,
@@ -444,7 +737,9 @@ impl bn::Entity for En {
             let mut positions = Vec::new();
             let effects: &[Effect] = if is_ranged {
                 let atk = &self.atks.ranged_atks[atk_idx];
-                positions.push(pos + dir);
+                positions.extend(
+                    atk.path(pos, pos + dir, |p| cmd.get_map(p).is_none_or(|t| t.blocking)),
+                );
                 &atk.effects
             } else {
                 let atk = &self.atks.melee_atks[&dir][atk_idx];
@@ -455,12 +750,41 @@ impl bn::Entity for En {
                 &atk.effects
             };
 
+            let moved = pos != start || self.vel.is_some();
+            let atk_mod = accuracy::attacker_mod(cmd, self, pos, moved);
+
             for target in positions.into_iter() {
                 for ef in effects {
                     match ef {
                         Effect::DoDmg(dmg_inst) => {
                             let dmg_inst = *dmg_inst;
-                            let hit = rand::random_bool(dmg_inst.acc);
+                            let opponent = cmd
+                                .get_ent(target)
+                                .filter(|e| e.special != Special::WallSentry && !e.dormant);
+                            let change = atk_mod + opponent.map_or(0.0, accuracy::target_mod);
+                            let hit = rand::random_bool(accuracy::chance(dmg_inst.acc, change));
+
+                            // Keep track of how well the player is aiming, and say when anything misses.
+                            if let Some(e) = opponent {
+                                if self.is_player {
+                                    unsafe {
+                                        if hit {
+                                            HITS += 1;
+                                        } else {
+                                            MISSES += 1;
+                                        }
+                                    }
+                                }
+                                if !hit {
+                                    LOG_MSGS.write().unwrap().push(
+                                        LogEvent::Miss {
+                                            from: *self.ch.content(),
+                                            to: *e.ch.content(),
+                                        }
+                                        .into(),
+                                    );
+                                }
+                            }
 
                             // Draw line with closure for ranged attacks and display hit_fx if necessary.
                             if is_ranged {
@@ -480,6 +804,7 @@ impl bn::Entity for En {
                                     cmd.queue(bn::Cmd::new_on(target).modify_entity(Box::new(
                                         move |e: &mut En| {
                                             let old = *e.hp;
+                                            let amount = e.dmg_taken(dmg_inst);
                                             e.apply_dmg(dmg_inst);
 
                                             // Only say anything if this is not a wall sentry.
@@ -487,15 +812,23 @@ impl bn::Entity for En {
                                                 let mut handle = LOG_MSGS.write().unwrap();
                                                 let e_ch = *e.ch.content();
                                                 handle.push(
-                                                    format!(
-                                                        "{} {} -> {}",
-                                                        ch,
-                                                        dmg_inst.total_dmg(),
-                                                        e_ch
-                                                    )
+                                                    if amount >= 0 {
+                                                        LogEvent::Damage {
+                                                            from: ch,
+                                                            to: e_ch,
+                                                            amount: amount as u32,
+                                                            kind: dmg_inst.kind,
+                                                        }
+                                                    } else {
+                                                        LogEvent::Heal {
+                                                            from: ch,
+                                                            to: e_ch,
+                                                            amount: amount.unsigned_abs(),
+                                                        }
+                                                    }
                                                     .into(),
                                                 );
-                                                if e_ch != WALL_SENTRY_CHAR {
+                                                if e_ch != glyphs().wall_sentry {
                                                     handle.push(
                                                         LogMsg::hp_change(old, &e.hp, e_ch)
                                                     );
@@ -511,6 +844,11 @@ impl bn::Entity for En {
                             }
                         }
                         Effect::Other(clos) => cmd.queue_many(clos(pos, target, &*cmd)),
+                        Effect::Inflict(st) => {
+                            if cmd.get_ent(target).is_some() {
+                                cmd.queue(inflict(target, *st));
+                            }
+                        }
                     }
                 }
             }
@@ -551,19 +889,13 @@ impl bn::Entity for En {
                         if possible {
                             // Check if there are any attacks that hit something in this direction,
                             // and if so, do the first one.
-                            if let Some(atks) = cur_en.atks.melee_atks.get(&disp) {
-                                'outer: for atk in atks.iter() {
-                                    for (n, atk_pos) in atk.place.iter().enumerate() {
-                                        if let Some(e) = cmd.get_ent(*atk_pos + pos)
-                                            && (e.is_player ^ cur_en.is_player)
-                                            && !e.dormant
-                                        {
-                                            do_attack(pos, cmd, disp, false, n);
-                                            acted = true;
-                                            break 'outer;
-                                        }
-                                    }
-                                }
+                            let hit = cur_en.atks.first_hit_in(pos, disp, |p| {
+                                cmd.get_ent(p)
+                                    .is_some_and(|e| (e.is_player ^ cur_en.is_player) && !e.dormant)
+                            });
+                            if let Some(n) = hit {
+                                do_attack(pos, cmd, disp, false, n);
+                                acted = true;
                             }
 
                             // If there has been no action, move if there is no entity in the way.
@@ -572,7 +904,7 @@ impl bn::Entity for En {
                                 if no_ent || unsafe { ENEMIES_REMAINING == 0 } {
                                     // Displace the entity if it generates next to a door.
                                     if !no_ent {
-                                        cmd.queue(bn::Cmd::new_on(cur_nx).move_to(cur_nx + disp));
+                                        cmd.queue_many(tile_presets::force_move(&*cmd, cur_nx, cur_nx + disp));
                                     }
                                     nx = Some(cur_nx);
                                     acted = true;
@@ -611,6 +943,16 @@ impl bn::Entity for En {
                             }
                         }
                     }
+                    ActionType::FireAt(idx, disp) => {
+                        // Check the target can still be hit before firing.
+                        if let Some(atk) = cur_en.atks.ranged_atks.get(idx)
+                            && pos.dist_squared(pos + disp) as u32 <= atk.range * atk.range
+                            && targeting::line_clear(cmd, pos, pos + disp)
+                        {
+                            acted = true;
+                            do_attack(pos, cmd, disp, true, idx)
+                        }
+                    }
                     ActionType::Summon(temp) => {
                         let pl = unsafe { PLAYER };
                         let best = closest_valid(&cmd, pl, pos);
@@ -619,6 +961,13 @@ impl bn::Entity for En {
                             let mut new_en = En::from_template(&temp, false, false);
                             new_en.acted = true;
                             new_en.special = Special::Minion;
+                            LOG_MSGS.write().unwrap().push(
+                                LogEvent::Summon {
+                                    by: *cur_en.ch.content(),
+                                    summoned: *new_en.ch.content(),
+                                }
+                                .into(),
+                            );
                             cmd.queue(bn::Cmd::new_on(p).create_entity(new_en));
                             acted = true;
                             unsafe { ENEMIES_REMAINING += 1; }
@@ -747,6 +1096,7 @@ impl bn::Entity for En {
 
                 if let Some(nx) = nx {
                     cmd.queue(bn::Cmd::new_here().move_to(nx));
+                    cmd.queue_many(tile_presets::leave(&*cmd, pos, Some(nx)));
 
                     // Do anything that the tile wants from us.
                     if let Some(t) = cmd.get_map(nx) {
@@ -768,7 +1118,7 @@ impl bn::Entity for En {
                         unsafe { PLAYER = nx }
                     }
 
-                    // Check this is a door, and reveal the room if we move into it.
+                    // Check this is a door, and lock the room if we move into it.
                     if self.is_player
                         && let Some(t) = cmd.get_map(pos)
                     {
@@ -799,20 +1149,23 @@ impl bn::Entity for En {
                             // Flag to say whether or not we are locking all the doors due to
                             // an enemy being detected in the room.
                             let mut dooring = false;
+                            // Enemies in the room that haven't woken up yet.
+                            let mut sleepers = 0;
 
-                            // Stack of positions to reveal.
+                            // Stack of positions to search.
                             let mut rev_stack = vec![nx];
                             let mut revd = HashSet::new();
 
-                            // Reveal all cells of the room via floodfill.
+                            // Find the doors of the room and anyone in it via floodfill.
                             while let Some(p) = rev_stack.pop() {
                                 if let Some(cl) = cmd.get_map(p) {
                                     // Already visited so ignore.
                                     if revd.contains(&p) {
                                         continue;
                                     } else {
-                                        // Push adjacent cells if this one is eligible.
-                                        if !cl.blocking {
+                                        // Push adjacent cells if this one is eligible. Gates and
+                                        // cracked walls are inside rooms, so look past them too.
+                                        if !cl.blocking || cl.mechanism.is_some() {
                                             for adj in p.get_all_adjacent_diagonal() {
                                                 if !cl.door {
                                                     rev_stack.push(adj);
@@ -822,10 +1175,6 @@ impl bn::Entity for En {
                                             }
                                         }
 
-                                        // Do the revealing.
-                                        cmd.queue(bn::Cmd::new_on(p).modify_tile(Box::new(
-                                            |t: &mut Tile| t.revealed = true,
-                                        )));
                                         revd.insert(p);
                                     }
                                 }
@@ -837,28 +1186,22 @@ impl bn::Entity for En {
                                     doors.push(p);
                                 }
 
-                                // Wake everyone up.
+                                // Anyone here means a fight. They wake up once they see the player.
                                 if p != pos
-                                    && let Some(_e) = cmd.get_ent(p)
+                                    && let Some(e) = cmd.get_ent(p)
                                 {
                                     dooring = true;
-                                    // Hack to stop the game crashing when an entity is displaced
-                                    // upon entering the room.
-                                    let mut e_pos = p;
-                                    if e_pos == nx {
-                                        e_pos = nx - pos + e_pos;
+                                    if e.dormant {
+                                        sleepers += 1;
                                     }
-                                    cmd.queue(bn::Cmd::new_on(e_pos).modify_entity(Box::new(
-                                        move |e: &mut En| {
-                                            e.dormant = false;
-                                            e.acted = true;
-                                            unsafe { ENEMIES_REMAINING += 1 };
-                                        },
-                                    )));
                                 }
                             }
 
                             if !doors.is_empty() && dooring {
+                                // Everyone in the room counts, awake or not, so the room stays
+                                // locked until they are all dead.
+                                unsafe { ENEMIES_REMAINING += sleepers };
+
                                 // Lock the doors
                                 for door in doors {
                                     cmd.queue(bn::Cmd::new_on(door).create_entity(En::new(
@@ -866,7 +1209,7 @@ impl bn::Entity for En {
                                         92,
                                         false,
                                         vec![ActionType::Wait],
-                                        WALL_SENTRY_CHAR.with(get_door_clr()),
+                                        glyphs().wall_sentry.with(get_door_clr()),
                                         Special::WallSentry,
                                         Vec::new(),
                                         AtkPat::empty(),
@@ -887,6 +1230,8 @@ impl bn::Entity for En {
         if let Some(v) = self.vel {
             let cur_nx = v + pos;
             let mut stop = false;
+            // Sliding into something outside of puzzles leaves the entity frozen.
+            let mut crashed = false;
 
             if verify_pos(cmd, cur_nx) {
                 let t = cmd.get_map(cur_nx).unwrap();
@@ -896,10 +1241,21 @@ impl bn::Entity for En {
                 handle_action_inner.borrow()(ActionType::TryMove(v), cmd, self, pos);
             } else {
                 stop = true;
+                crashed = unsafe { PUZZLE.is_none() };
             }
 
             cmd.queue(
                 bn::Cmd::new_here().modify_entity(Box::new(move |e: &mut En| {
+                    if crashed {
+                        e.add_status(Status::new(StatusKind::Frozen, status::ICE_FREEZE));
+                        LOG_MSGS.write().unwrap().push(
+                            LogEvent::StatusGained {
+                                who: *e.ch.content(),
+                                kind: StatusKind::Frozen,
+                            }
+                            .into(),
+                        );
+                    }
                     if stop {
                         e.vel = None;
                     } else if e.is_player {
@@ -914,12 +1270,19 @@ impl bn::Entity for En {
                 })),
             );
         } else {
-            let cur_act = if self.is_player {
+            let cur_act = if held {
+                ActionType::Wait
+            } else if self.is_player {
                 unsafe { ACTION.clone() }
             } else {
                 self.actions[self.count].clone()
             };
             (_, acted, new_count) = handle_action_inner.borrow()(cur_act, cmd, self, pos);
+
+            // Carry on from the same action once we can act again.
+            if held {
+                new_count = self.count;
+            }
         }
 
         // Increase global time if player, otherwise set the flag to prevent multi actions.
@@ -980,3 +1343,46 @@ impl bn::Entity for En {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dummy(max_hp: u32) -> En {
+        En::new(max_hp, false, Vec::new(), 'e'.stylize(), Special::Not, Vec::new(), AtkPat::empty(), false)
+    }
+
+    #[test]
+    fn add_status_keeps_longest() {
+        let mut e = dummy(3);
+        e.add_status(Status::new(StatusKind::Stun, 2));
+        e.add_status(Status::new(StatusKind::Stun, 1));
+        e.add_status(Status::new(StatusKind::Poison, 1));
+        assert_eq!(e.status(StatusKind::Stun), Some(&Status::new(StatusKind::Stun, 2)));
+
+        e.add_status(Status::new(StatusKind::Stun, 4));
+        assert_eq!(e.status(StatusKind::Stun).map(|s| s.amount), Some(4));
+        assert_eq!(e.statuses.len(), 2);
+    }
+
+    #[test]
+    fn tick_statuses() {
+        let mut e = dummy(3);
+        e.add_status(Status::new(StatusKind::Poison, 2));
+        e.add_status(Status::new(StatusKind::Slow, 1));
+        e.add_status(Status::new(StatusKind::Shield, 1));
+        assert!(e.held());
+
+        e.tick_statuses();
+        assert_eq!(*e.hp, 2);
+        assert_eq!(e.status(StatusKind::Poison).map(|s| s.amount), Some(1));
+        assert!(e.status(StatusKind::Slow).is_none());
+        assert!(!e.held());
+
+        e.tick_statuses();
+        assert_eq!(*e.hp, 1);
+        assert!(e.status(StatusKind::Poison).is_none());
+        // Shields are used up by hits, not time.
+        assert_eq!(e.status(StatusKind::Shield).map(|s| s.amount), Some(1));
+    }
+}
@@ -0,0 +1,89 @@
+//! Works out what the player can see, and wakes up anything that sees them.
+
+use crate::entity::{En, PLAYER, PUZZLE};
+use crate::{Mechanism, Point, REVEALED, Tile, bn};
+use crossterm::style::{self, Stylize};
+use std::collections::HashSet;
+use std::sync::{LazyLock, RwLock};
+
+/// Furthest anything can see, in tiles.
+pub const SIGHT_RANGE: i32 = 10;
+/// Colour of remembered tiles that can't currently be seen.
+pub const DIM_CLR: style::Color = style::Color::Rgb { r: 55, g: 55, b: 55 };
+
+/// Every position the player could see after the last turn.
+pub static VISIBLE: LazyLock<RwLock<HashSet<Point>>> =
+    LazyLock::new(|| RwLock::new(HashSet::new()));
+
+type StyleCh = style::StyledContent<char>;
+
+/// Returns true if the tile can be seen through. Doors can be seen but not past, so rooms can
+/// only be looked into from their doorways. Gates are bars, so they can be seen through.
+fn transparent(t: &Tile) -> bool {
+    !t.door && (!t.blocking || matches!(t.mechanism, Some(Mechanism::Gate(_))))
+}
+
+/// Returns true if something at from can see to, ignoring the tiles at either end.
+pub fn sight_line(map: &bn::Map<En>, from: Point, to: Point) -> bool {
+    from.dist_squared(to) <= SIGHT_RANGE * SIGHT_RANGE
+        && Point::plot_line(from, to)
+            .skip(1)
+            .take_while(|p| *p != to)
+            .all(|p| map.get_map(p).is_some_and(transparent))
+}
+
+/// Returns true if the player can see the position. Everything can be seen in puzzles.
+pub fn visible(pos: Point) -> bool {
+    unsafe { PUZZLE.is_some() } || *REVEALED.read().unwrap() || VISIBLE.read().unwrap().contains(&pos)
+}
+
+/// Returns how the position looks while it can't be seen: dimmed if it has been seen before, and
+/// blank otherwise. Anything standing there is hidden.
+pub fn remembered(map: &bn::Map<En>, pos: Point) -> StyleCh {
+    match map.get_map(pos) {
+        Some(t) if t.revealed => (*bn::Tile::repr(t).content()).with(DIM_CLR),
+        _ => ' '.stylize(),
+    }
+}
+
+/// Work out what the player can see from where they are now, remembering every tile they see.
+/// Dormant enemies that can see the player wake up, unless the player is standing in a doorway,
+/// so only the room the player is in can be woken. They were already counted as remaining when
+/// the room was locked.
+pub fn update(map: &mut bn::Map<En>) {
+    if unsafe { PUZZLE.is_some() } {
+        return;
+    }
+    let pl = unsafe { PLAYER };
+
+    let mut seen = HashSet::new();
+    for y in -SIGHT_RANGE..=SIGHT_RANGE {
+        for x in -SIGHT_RANGE..=SIGHT_RANGE {
+            let p = pl + Point::new(x, y);
+            if map.get_map(p).is_some() && sight_line(map, pl, p) {
+                seen.insert(p);
+            }
+        }
+    }
+    for &p in &seen {
+        if let Some(t) = map.get_map_mut(p) {
+            t.revealed = true;
+        }
+    }
+
+    if !map.get_map(pl).is_some_and(|t| t.door) {
+        let woken: Vec<Point> = map
+            .get_entities()
+            .filter(|(p, e)| e.dormant && sight_line(map, **p, pl))
+            .map(|(p, _)| *p)
+            .collect();
+        for p in woken {
+            if let Some(e) = map.get_ent_mut(p) {
+                e.dormant = false;
+                e.acted = true;
+            }
+        }
+    }
+
+    *VISIBLE.write().unwrap() = seen;
+}
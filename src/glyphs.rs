@@ -0,0 +1,170 @@
+//! Contains the character sets used to draw the game.
+
+use crate::settings::SETTINGS;
+use std::sync::LazyLock;
+
+/// Setting this environment variable to anything other than 0 forces the ascii glyph set.
+pub const ASCII_ENV_VAR: &str = "UNTITLED_BANDIT_ASCII";
+
+/// Every character drawn by the game that some terminals or fonts may not be able to display.
+#[derive(Clone, Debug)]
+pub struct GlyphSet {
+    /// Arrows pointing down, left, up and right, in that order.
+    pub arrows: [char; 4],
+    /// Arrows in the eight directions, ordered like [bandit::Point::get_all_adjacent_diagonal].
+    pub diag_arrows: [char; 8],
+    /// A key lying on the floor.
+    pub key: char,
+    /// A door that needs a key to open.
+    pub locked_door: char,
+    /// The wall that seals doors while enemies remain.
+    pub wall_sentry: char,
+    /// The final boss.
+    pub boss: char,
+    /// A teleporter.
+    pub teleporter: char,
+    /// A closed gate held open by a pressure plate.
+    pub gate: char,
+    /// A wall that explosions can destroy.
+    pub cracked_wall: char,
+    /// Heavy versions of the four orthogonal attack characters.
+    pub thicc_four_pos_atk: [char; 4],
+    /// Attack characters in the eight directions.
+    pub eight_pos_atk: [char; 8],
+    /// Corners and edges of a ring attack.
+    pub ring: [char; 6],
+    /// Diagonal attack characters.
+    pub diag: [char; 4],
+    /// Bends of the attacks a knight move away.
+    pub elbows: [char; 4],
+    /// Character of an attack that looks different to the default one.
+    pub weird_atk: char,
+    /// Centre of a holy attack.
+    pub holy_cross: char,
+    /// Tail of a holy attack.
+    pub holy_line: char,
+}
+
+/// The default glyph set.
+pub const UNICODE: GlyphSet = GlyphSet {
+    arrows: ['↓', '←', '↑', '→'],
+    diag_arrows: ['↙', '←', '↖', '↓', '↑', '↘', '→', '↗'],
+    // This does look like a key when printed.
+    key: '⚷',
+    locked_door: '╬',
+    wall_sentry: '█',
+    boss: 'Ω',
+    teleporter: '◎',
+    gate: '▥',
+    cracked_wall: '▒',
+    thicc_four_pos_atk: ['═', '║', '═', '║'],
+    eight_pos_atk: ['╱', '-', '╲', '|', '|', '╲', '-', '╱'],
+    ring: ['╔', '═', '╗', '║', '╝', '╚'],
+    diag: ['╱', '╲', '╱', '╲'],
+    elbows: ['└', '┘', '┐', '┌'],
+    weird_atk: '☼',
+    holy_cross: '╬',
+    holy_line: '║',
+};
+
+/// Glyph set that only uses ascii characters, for terminals that cannot display the default one.
+pub const ASCII: GlyphSet = GlyphSet {
+    // 'v' is an enemy, '^' is a spike trap and '>' is the exit, so conveyors point with braces, or
+    // with a comma and a backtick sitting low and high in the cell.
+    arrows: [',', '{', '`', '}'],
+    diag_arrows: ['/', '<', '\\', 'v', '^', '\\', '>', '/'],
+    key: '$',
+    locked_door: '[',
+    wall_sentry: ')',
+    boss: '&',
+    teleporter: ';',
+    gate: ']',
+    cracked_wall: ':',
+    thicc_four_pos_atk: ['=', 'H', '=', 'H'],
+    eight_pos_atk: ['/', '-', '\\', '|', '|', '\\', '-', '/'],
+    ring: ['+', '-', '+', '|', '+', '+'],
+    diag: ['/', '\\', '/', '\\'],
+    elbows: ['+', '+', '+', '+'],
+    weird_atk: '~',
+    holy_cross: '+',
+    holy_line: 'H',
+};
+
+static GLYPHS: LazyLock<&'static GlyphSet> =
+    LazyLock::new(|| if use_ascii() { &ASCII } else { &UNICODE });
+
+/// Returns true if the ascii glyph set was requested by the environment or the settings file.
+pub fn use_ascii() -> bool {
+    match std::env::var(ASCII_ENV_VAR) {
+        Ok(val) => val != "0",
+        Err(_) => SETTINGS.ascii,
+    }
+}
+
+/// Return the glyph set in use. Decided the first time this is called and never changes after.
+pub fn glyphs() -> &'static GlyphSet {
+    *GLYPHS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DOOR_CHAR, ICE_CHAR, items::Item, templates, tile_presets};
+
+    #[test]
+    fn ascii_glyphs_distinct() {
+        let mut map_glyphs = ASCII.arrows.to_vec();
+        map_glyphs.extend([
+            ASCII.key,
+            ASCII.locked_door,
+            ASCII.wall_sentry,
+            ASCII.boss,
+            ASCII.teleporter,
+            ASCII.gate,
+            ASCII.cracked_wall,
+        ]);
+
+        // Everything else that can be on the floor at the same time as these.
+        let mut others = vec![
+            '#',
+            '.',
+            '>',
+            DOOR_CHAR,
+            ICE_CHAR,
+            tile_presets::SPIKE_CHAR,
+            tile_presets::PLATE_CHAR,
+            tile_presets::OPEN_GATE_CHAR,
+            templates::PLAYER_CHARACTER,
+        ];
+        others.extend(Item::ALL.map(|it| *it.ch().content()));
+        let (normal, elite) = templates::get_templates();
+        others.extend(
+            normal
+                .iter()
+                .chain(elite.iter())
+                .chain([&templates::get_minion()])
+                .map(|t| *t.ch.content())
+                .filter(|&ch| ch != glyphs().boss),
+        );
+
+        // Attacks are drawn over the floor too, so they can't be mistaken for it either.
+        let atks = [
+            &ASCII.diag_arrows[..],
+            &ASCII.thicc_four_pos_atk,
+            &ASCII.eight_pos_atk,
+            &ASCII.ring,
+            &ASCII.diag,
+            &ASCII.elbows,
+            &[ASCII.weird_atk, ASCII.holy_cross, ASCII.holy_line],
+        ]
+        .concat();
+
+        for (n, &ch) in map_glyphs.iter().enumerate() {
+            // Letters are enemies and digits are health.
+            assert!(ch.is_ascii_punctuation(), "{ch:?} is not punctuation");
+            assert!(!map_glyphs[n + 1..].contains(&ch), "{ch:?} is used twice");
+            assert!(!others.contains(&ch), "{ch:?} is already on the floor");
+            assert!(!atks.contains(&ch), "{ch:?} is also an attack");
+        }
+    }
+}
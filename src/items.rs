@@ -0,0 +1,278 @@
+//! Items the player can pick up and use later.
+
+use crate::{ActionType, Point, Vfx, bn, templates, tile_presets};
+use crate::attacks::{DmgInst, Effect};
+use crate::entity::{En, LOG_MSGS, LogEvent, LogMsg, PLAYER, Special, inflict};
+use crate::status::{self, Status, StatusKind};
+use crossterm::style::{self, Stylize};
+use std::{fmt, sync::RwLock};
+
+type StyleCh = style::StyledContent<char>;
+type ItemFn = fn(&bn::Map<En>, &En, Point) -> Vec<bn::Cmd<En>>;
+
+/// Most items the player can hold at once.
+pub const MAX_ITEMS: usize = 3;
+/// Health restored by a draught.
+const DRAUGHT_HEAL: u32 = 3;
+/// Damage done by a bomb.
+const BOMB_DMG: u32 = 2;
+/// Manhattan radius of a bomb's explosion.
+const BOMB_RADIUS: i32 = 2;
+/// Damage done to anything a push charm pushes into something.
+const PUSH_DMG: u32 = 1;
+/// Furthest a blink scroll can move the player, using manhattan distance.
+const BLINK_RANGE: i32 = 4;
+
+/// Items the player is holding, in the order they were picked up.
+pub static INVENTORY: RwLock<Vec<Item>> = RwLock::new(Vec::new());
+
+/// Something the player can carry and use instead of moving.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Item {
+    /// Heals the player.
+    Draught,
+    /// Damages everything around the player.
+    Bomb,
+    /// Pushes away everything next to the player.
+    PushCharm,
+    /// Moves the player away from enemies.
+    BlinkScroll,
+}
+
+impl Item {
+    /// Every item, for choosing one at random.
+    pub const ALL: [Item; 4] = [Item::Draught, Item::Bomb, Item::PushCharm, Item::BlinkScroll];
+
+    /// Returns the colour of the item.
+    pub fn clr(self) -> style::Color {
+        match self {
+            Item::Draught => style::Color::Green,
+            Item::Bomb => style::Color::Red,
+            Item::PushCharm => style::Color::Cyan,
+            Item::BlinkScroll => style::Color::Magenta,
+        }
+    }
+
+    /// Returns the character used to show the item.
+    pub fn ch(self) -> StyleCh {
+        let ch = match self {
+            Item::Draught => '!',
+            Item::Bomb => '%',
+            Item::PushCharm => '"',
+            Item::BlinkScroll => '?',
+        };
+        ch.with(self.clr())
+    }
+
+    /// Returns the action the player takes when using the item.
+    pub fn action(self) -> ActionType {
+        let f: ItemFn = match self {
+            Item::Draught => drink,
+            Item::Bomb => explode,
+            Item::PushCharm => push,
+            Item::BlinkScroll => blink,
+        };
+        ActionType::Arbitrary(Box::new(f))
+    }
+
+    /// Returns true if using the item at the given position would do anything, so items aren't
+    /// wasted.
+    pub fn usable(self, map: &bn::Map<En>, pos: Point) -> bool {
+        match self {
+            Item::Draught => map.get_ent(pos).is_some_and(|e| *e.hp < e.hp.max),
+            // Bombs can always knock down walls, even with nothing to hit.
+            Item::Bomb => true,
+            Item::PushCharm => pos
+                .get_all_adjacent_diagonal()
+                .into_iter()
+                .any(|p| map.get_ent(p).is_some_and(targetable)),
+            Item::BlinkScroll => blink_dest(map, pos).is_some(),
+        }
+    }
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Item::Draught => "Draught",
+            Item::Bomb => "Bomb",
+            Item::PushCharm => "Push charm",
+            Item::BlinkScroll => "Blink scroll",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Give the player an item. Returns false if they can't carry any more.
+pub fn add(item: Item) -> bool {
+    let mut inv = INVENTORY.write().unwrap();
+    if inv.len() >= MAX_ITEMS {
+        return false;
+    }
+    inv.push(item);
+    true
+}
+
+/// Returns the item in the given slot, if there is one, without taking it.
+pub fn get(slot: usize) -> Option<Item> {
+    INVENTORY.read().unwrap().get(slot).copied()
+}
+
+/// Returns the inventory slot used by the key, if it is one of the number keys for a slot.
+pub fn slot_for_key(c: char) -> Option<usize> {
+    let slot = (c.to_digit(10)? as usize).checked_sub(1)?;
+    (slot < MAX_ITEMS).then_some(slot)
+}
+
+/// Take the item in the given slot away from the player, if there is one.
+pub fn take(slot: usize) -> Option<Item> {
+    let mut inv = INVENTORY.write().unwrap();
+    if slot < inv.len() {
+        Some(inv.remove(slot))
+    } else {
+        None
+    }
+}
+
+/// Returns a command that applies the damage instance to the entity at the target, logging it
+/// the same way as an attack.
+fn hit(target: Point, inst: DmgInst, from: char) -> bn::Cmd<En> {
+    bn::Cmd::new_on(target).modify_entity(Box::new(move |e: &mut En| {
+        let old = *e.hp;
+        let amount = e.dmg_taken(inst);
+        e.apply_dmg(inst);
+
+        let mut handle = LOG_MSGS.write().unwrap();
+        let to = *e.ch.content();
+        handle.push(
+            if amount >= 0 {
+                LogEvent::Damage {
+                    from,
+                    to,
+                    amount: amount as u32,
+                    kind: inst.kind,
+                }
+            } else {
+                LogEvent::Heal {
+                    from,
+                    to,
+                    amount: amount.unsigned_abs(),
+                }
+            }
+            .into(),
+        );
+        handle.push(LogMsg::hp_change(old, &e.hp, to));
+    }))
+}
+
+/// Returns true if the entity can be affected by items.
+fn targetable(e: &En) -> bool {
+    !e.is_player && !e.dormant && e.special != Special::WallSentry
+}
+
+/// Heal the player.
+fn drink(_map: &bn::Map<En>, en: &En, pos: Point) -> Vec<bn::Cmd<En>> {
+    vec![hit(pos, DmgInst::heal(DRAUGHT_HEAL), *en.ch.content())]
+}
+
+/// Blow up everything around the player, but not the player.
+fn explode(map: &bn::Map<En>, en: &En, pos: Point) -> Vec<bn::Cmd<En>> {
+    let atk = templates::get_explosion(BOMB_DMG, BOMB_RADIUS, ' '.on_red());
+    let mut cmds = Vec::new();
+
+    for (p, fx) in atk.fx {
+        if p != Point::ORIGIN {
+            cmds.push(bn::Cmd::new_on(p + pos).create_effect(fx));
+        }
+    }
+
+    for target in atk.place.iter().filter(|p| **p != Point::ORIGIN).map(|p| *p + pos) {
+        cmds.extend(tile_presets::crumble(map, target));
+        if !map.get_ent(target).is_some_and(targetable) {
+            continue;
+        }
+        for ef in &atk.effects {
+            match ef {
+                Effect::DoDmg(inst) => {
+                    if rand::random_bool(inst.acc) {
+                        cmds.push(hit(target, *inst, *en.ch.content()));
+                    }
+                }
+                // Walls have already been dealt with.
+                Effect::Other(_) => {}
+                Effect::Inflict(st) => cmds.push(inflict(target, *st)),
+            }
+        }
+        // Anything left standing is dazed by the blast.
+        cmds.push(inflict(target, Status::new(StatusKind::Stun, status::BOMB_STUN)));
+    }
+
+    cmds
+}
+
+/// Push everything next to the player away from them.
+fn push(map: &bn::Map<En>, _en: &En, pos: Point) -> Vec<bn::Cmd<En>> {
+    let Effect::Other(clos) = templates::get_push_effect(PUSH_DMG, 1) else {
+        unreachable!("push effects are always Effect::Other");
+    };
+
+    pos.get_all_adjacent_diagonal()
+        .into_iter()
+        .filter(|p| map.get_ent(*p).is_some_and(targetable))
+        .flat_map(|p| clos(pos, p, map))
+        .collect()
+}
+
+/// Returns the nearby tile that is furthest from any enemy, or None if there is nowhere to go.
+fn blink_dest(map: &bn::Map<En>, pos: Point) -> Option<Point> {
+    let free = |p: Point| {
+        map.get_map(p)
+            .is_some_and(|t| t.revealed && !t.blocking && !t.slippery && t.step_effect.is_none())
+            && map.get_ent(p).is_none()
+    };
+    let enemies: Vec<Point> = map
+        .get_entities()
+        .filter(|(_, e)| targetable(e))
+        .map(|(p, _)| *p)
+        .collect();
+
+    let mut best = None;
+    for y in -BLINK_RANGE..=BLINK_RANGE {
+        for x in -BLINK_RANGE..=BLINK_RANGE {
+            let dest = pos + Point::new(x, y);
+            if pos.manhattan_dist(dest) > BLINK_RANGE
+                || !free(dest)
+                || !Point::plot_line(pos, dest).skip(1).all(free)
+            {
+                continue;
+            }
+
+            // Prefer being far from enemies, then far from where we started.
+            let score = (
+                enemies.iter().map(|e| e.dist_squared(dest)).min().unwrap_or(0),
+                pos.dist_squared(dest),
+            );
+            if best.is_none_or(|(_, best_score)| score > best_score) {
+                best = Some((dest, score));
+            }
+        }
+    }
+
+    best.map(|(dest, _)| dest)
+}
+
+/// Move the player to the nearby tile that is furthest from any enemy.
+fn blink(map: &bn::Map<En>, _en: &En, pos: Point) -> Vec<bn::Cmd<En>> {
+    let Some(dest) = blink_dest(map, pos) else {
+        return Vec::new();
+    };
+
+    unsafe {
+        PLAYER = dest;
+    }
+    let mut cmds = vec![
+        bn::Cmd::new_on(pos).create_effect(Vfx::opaque_with_clr('*', style::Color::Magenta, 4)),
+    ];
+    cmds.extend(tile_presets::force_move(map, pos, dest));
+    cmds
+}
@@ -0,0 +1,221 @@
+//! Works out where each window of the game goes based on the size of the terminal.
+
+use crate::{Point, TERMINAL_HGT, TERMINAL_WID, entity::KEY_CLRS, items::MAX_ITEMS};
+use crossterm::{cursor, event, queue, style, terminal};
+use std::io::{self, Write};
+
+/// Columns and rows left empty at the edges of the terminal.
+const MARGIN: i32 = 1;
+/// Rows at the top of the terminal kept free for the puzzle window.
+const TOP: i32 = 4;
+
+/// Width of the text in the stats window.
+pub const STATS_WID: usize = 15;
+/// Height of the stats window including its outline.
+const STATS_HGT: i32 = 9;
+/// Width and height of the grid in the attacks window.
+pub const ATKS_WID: usize = 5;
+/// Height of the attacks window including its outline and the hit chance below the grid.
+const ATKS_HGT: i32 = ATKS_WID as i32 + 3;
+/// Width of the text in the keys window.
+pub const KEYS_WID: usize = KEY_CLRS.len() * 4 + 1;
+/// Height of the keys window including its outline.
+const KEYS_HGT: i32 = 6;
+/// Width of the text in the items window.
+pub const ITEMS_WID: usize = KEYS_WID;
+/// Height of the items window including its outline.
+const ITEMS_HGT: i32 = MAX_ITEMS as i32 + 5;
+/// Width of the text in the log window.
+pub const LOG_WID: usize = 29;
+/// Fewest messages the log window will show.
+const MIN_LOG_LINES: usize = 3;
+/// Rows of the log window that are not messages, including its outline.
+const LOG_EXTRA_HGT: i32 = 5;
+/// Width of the text in the debug window.
+pub const DEBUG_WID: usize = 24;
+/// Height of the debug window including its outline.
+const DEBUG_HGT: i32 = 9;
+/// Width of the text in the seed window.
+pub const SEED_WID: usize = 24;
+/// Height of the seed window including its outline.
+const SEED_HGT: i32 = 5;
+/// Width of the text in the puzzle window.
+pub const PUZZLE_WID: usize = 16;
+
+/// Smallest width of the map view.
+pub const MIN_VIEW_WID: u32 = 21;
+/// Smallest height of the map view.
+pub const MIN_VIEW_HGT: u32 = 11;
+
+/// Width of the windows to the left of the map view, including outlines.
+const LEFT_COL_WID: i32 = DEBUG_WID as i32 + 2;
+/// Width of the windows to the right of the map view, including outlines.
+const RIGHT_COL_WID: i32 = LOG_WID as i32 + 2;
+/// Height of the windows to the left of the map view, including outlines.
+const LEFT_COL_HGT: i32 = STATS_HGT
+    + ATKS_HGT
+    + if cfg!(debug_assertions) {
+        DEBUG_HGT
+    } else {
+        SEED_HGT
+    };
+/// Smallest height of the windows to the right of the map view, including outlines.
+const RIGHT_COL_HGT: i32 = KEYS_HGT + ITEMS_HGT + MIN_LOG_LINES as i32 + LOG_EXTRA_HGT;
+/// Rows needed by the main menu and end screen, whose outlines reach down to row 30.
+const MENU_HGT: i32 = 31;
+
+const fn max(a: i32, b: i32) -> i32 {
+    if a > b { a } else { b }
+}
+
+/// Smallest terminal width the game can be displayed in.
+pub const MIN_WID: u16 =
+    (MARGIN * 2 + LEFT_COL_WID + RIGHT_COL_WID + MIN_VIEW_WID as i32 + 4) as u16;
+/// Smallest terminal height the game can be displayed in.
+pub const MIN_HGT: u16 = max(
+    TOP + max(max(LEFT_COL_HGT, RIGHT_COL_HGT), MIN_VIEW_HGT as i32 + 2) + MARGIN,
+    MENU_HGT,
+) as u16;
+
+/// Positions of the top left corner of each window, and the size of the ones that change size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Layout {
+    /// Width of the terminal this layout was made for.
+    pub wid: u16,
+    /// Height of the terminal this layout was made for.
+    pub hgt: u16,
+    /// Top left corner of the map view.
+    pub game: Point,
+    /// Number of tiles visible horizontally in the map view. Always odd.
+    pub view_wid: u32,
+    /// Number of tiles visible vertically in the map view. Always odd.
+    pub view_hgt: u32,
+    /// Top left corner of the stats window.
+    pub stats: Point,
+    /// Top left corner of the attacks window.
+    pub atks: Point,
+    /// Top left corner of the keys window.
+    pub keys: Point,
+    /// Top left corner of the items window.
+    pub items: Point,
+    /// Top left corner of the log window.
+    pub log: Point,
+    /// Number of messages the log window shows.
+    pub log_lines: usize,
+    /// Top left corner of the debug window.
+    pub debug: Point,
+    /// Top left corner of the seed window.
+    pub seed: Point,
+    /// Top left corner of the puzzle window.
+    pub puzzle: Point,
+}
+
+impl Layout {
+    /// Work out the layout for a terminal of the given size. Returns None if it is too small.
+    pub fn new(wid: u16, hgt: u16) -> Option<Self> {
+        if wid < MIN_WID || hgt < MIN_HGT {
+            return None;
+        }
+
+        let (w, h) = (wid as i32, hgt as i32);
+        let game_x = MARGIN + LEFT_COL_WID + 1;
+        let right_x = w - MARGIN - RIGHT_COL_WID;
+
+        // Take off the outline, and keep the sizes odd so there is a middle tile.
+        let mut view_wid = right_x - 1 - game_x - 2;
+        let mut view_hgt = h - TOP - MARGIN - 2;
+        if view_wid % 2 == 0 {
+            view_wid -= 1;
+        }
+        if view_hgt % 2 == 0 {
+            view_hgt -= 1;
+        }
+
+        // The left column is right aligned against the map view.
+        let left_edge = game_x - 1;
+        let stats = Point::new(left_edge - (STATS_WID as i32 + 2), TOP);
+        let atks = Point::new(left_edge - (ATKS_WID as i32 + 2), TOP + STATS_HGT);
+        let bottom = Point::new(left_edge - LEFT_COL_WID, atks.y + ATKS_HGT);
+
+        let items = Point::new(right_x, TOP + KEYS_HGT);
+        let log = Point::new(right_x, items.y + ITEMS_HGT);
+        let log_lines = (h - MARGIN - log.y - LOG_EXTRA_HGT) as usize;
+
+        Some(Self {
+            wid,
+            hgt,
+            game: Point::new(game_x, TOP),
+            view_wid: view_wid as u32,
+            view_hgt: view_hgt as u32,
+            stats,
+            atks,
+            keys: Point::new(right_x, TOP),
+            items,
+            log,
+            log_lines,
+            debug: bottom,
+            seed: bottom,
+            puzzle: Point::new(w / 2 - PUZZLE_WID as i32 / 2, 0),
+        })
+    }
+
+    /// Work out the layout for the current size of the terminal. Returns None if it is too small.
+    pub fn current() -> Option<Self> {
+        let (wid, hgt) = terminal::size().unwrap_or((TERMINAL_WID, TERMINAL_HGT));
+        Self::new(wid, hgt)
+    }
+
+    /// Return the layout for the current terminal. If it is too small, tell the player and wait
+    /// until it is resized to something big enough.
+    pub fn wait_for_fit() -> Self {
+        loop {
+            if let Some(lay) = Self::current() {
+                return lay;
+            }
+
+            let (wid, hgt) = terminal::size().unwrap_or((0, 0));
+            show_too_small(wid, hgt);
+
+            while !matches!(event::read(), Ok(event::Event::Resize(..))) {}
+        }
+    }
+
+    /// Return the offset from the top left of the map view of the given terminal position, or
+    /// None if it is outside of the map view.
+    pub fn view_offset(&self, col: u16, row: u16) -> Option<Point> {
+        // Skip the outline.
+        let off = Point::new(col as i32, row as i32) - self.game - Point::new(1, 1);
+
+        if off.x >= 0 && off.y >= 0 && off.x < self.view_wid as i32 && off.y < self.view_hgt as i32 {
+            Some(off)
+        } else {
+            None
+        }
+    }
+
+    /// Return the x position that horizontally centres something of the given width.
+    pub fn centred(&self, wid: usize) -> i32 {
+        max((self.wid as i32 - wid as i32) / 2, 0)
+    }
+}
+
+/// Clear the screen and tell the player that the terminal needs to be bigger.
+pub fn show_too_small(wid: u16, hgt: u16) {
+    let mut handle = io::stdout();
+    let lines = [
+        String::from("Terminal too small!"),
+        format!("Need at least {MIN_WID}x{MIN_HGT},"),
+        format!("currently {wid}x{hgt}."),
+        String::from("Resize to continue."),
+    ];
+
+    let _ = queue!(handle, terminal::Clear(terminal::ClearType::All));
+    for (y, ln) in lines.iter().enumerate() {
+        let _ = queue!(
+            handle,
+            cursor::MoveTo(0, y as u16),
+            style::Print(ln)
+        );
+    }
+    let _ = handle.flush();
+}
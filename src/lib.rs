@@ -33,9 +33,9 @@ pub const DOOR_CLRS: [style::Color; KILL_SCREEN] = [
     style::Color::White,
 ];
 
-/// Width of the terminal in characters.
+/// Width the terminal is set to on startup, in characters. The layout adapts if it ends up different.
 pub const TERMINAL_WID: u16 = 120;
-/// Height of the terminal in characters.
+/// Height the terminal is set to on startup, in characters. The layout adapts if it ends up different.
 pub const TERMINAL_HGT: u16 = 30;
 
 pub const WALL_CLRS: [style::Color; KILL_SCREEN] = [
@@ -78,6 +78,45 @@ pub mod save_file;
 pub mod datum;
 pub use datum::Datum;
 
+pub mod settings;
+
+pub mod glyphs;
+pub use glyphs::glyphs;
+
+pub mod layout;
+
+pub mod camera;
+
+pub mod travel;
+
+pub mod look;
+
+pub mod threat;
+
+pub mod log_view;
+
+pub mod overview;
+
+pub mod modifiers;
+
+pub mod items;
+
+pub mod upgrades;
+
+pub mod classes;
+
+pub mod targeting;
+
+pub mod status;
+
+pub mod accuracy;
+
+pub mod fov;
+
+pub mod themes;
+
+pub mod cli;
+
 /// Return the path to the assets directory of the project.
 pub fn get_assets_path() -> std::path::PathBuf {
     let mut this_path = std::env::current_exe().expect("Failed to get path to project");
@@ -101,10 +140,18 @@ pub fn print_win(win_cont: &windowed::Container<style::StyledContent<char>>) {
     let _ = handle.flush();
 }
 
-
 /// Returns the colour of doors on the current floor.
 pub fn get_door_clr() -> style::Color {
-    DOOR_CLRS[unsafe { FLOORS_CLEARED as usize }]
+    themes::current()
+        .door_clr()
+        .unwrap_or(DOOR_CLRS[unsafe { FLOORS_CLEARED as usize }])
+}
+
+/// Returns the colour of the walls and floors on the current floor.
+pub fn get_wall_clr() -> style::Color {
+    themes::current()
+        .wall_clr()
+        .unwrap_or(WALL_CLRS[unsafe { FLOORS_CLEARED as usize }])
 }
 
 /// Required to make Tiles Clone.
@@ -137,6 +184,26 @@ pub struct Tile {
     /// Something that occurs when an entity steps on this tile. The arguments are the position of
     /// the tile and the map the tile is in. It should return all the commands to be executed.
     pub step_effect: Option<Box<dyn StepEffectFn>>,
+    /// Something about the tile that other tiles or attacks can interact with.
+    pub mechanism: Option<Mechanism>,
+}
+
+/// Parts of a tile that change when something happens elsewhere. Links are None until they are
+/// connected to another tile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mechanism {
+    /// Spikes that damage whatever steps on them.
+    Spikes,
+    /// A pressure plate that holds the gate at the given position open.
+    Plate(Option<Point>),
+    /// A pressure plate that opens the gate at the given position for good.
+    Latch(Option<Point>),
+    /// A gate held open by the pressure plate at the given position.
+    Gate(Option<Point>),
+    /// A teleporter sending whatever steps on it to the given position.
+    Teleporter(Option<Point>),
+    /// A wall that is destroyed by explosions.
+    Cracked,
 }
 
 impl Tile {
@@ -151,14 +218,14 @@ impl Tile {
     /// If the tile is locked and the corresponding key has been collected, unlocks the door.
     pub fn unlock(&mut self) {
         if self.unlockable() {
-            let lck_val = self.locked.take().unwrap() as usize;
+            let lck_val = self.locked.take().unwrap();
             self.blocking = false;
             self.ch = Some(DOOR_CHAR.with(get_door_clr()));
-            unsafe { crate::entity::KEYS_COLLECTED[lck_val % entity::KEY_CLRS.len()] -= 1 }
+            unsafe { crate::entity::KEYS_COLLECTED[lck_val as usize % entity::KEY_CLRS.len()] -= 1 }
             entity::LOG_MSGS
                 .write()
                 .unwrap()
-                .push(format!("{} unlocks door", templates::PLAYER_CHARACTER).into());
+                .push(entity::LogEvent::DoorUnlocked(lck_val).into());
         }
     }
 
@@ -185,6 +252,7 @@ impl Default for Tile {
             slippery: false,
             step_effect: None,
             locked: None,
+            mechanism: None,
         }
     }
 }
@@ -199,15 +267,14 @@ impl bn::Tile for Tile {
     type Repr = StyleCh;
 
     fn repr(&self) -> Self::Repr {
-        let flrs = unsafe { crate::entity::FLOORS_CLEARED as usize };
         if !self.revealed && !*REVEALED.read().unwrap() {
             ' '.stylize()
         } else if let Some(c) = self.ch {
             c
         } else if self.blocking {
-            '#'.with(WALL_CLRS[flrs])
+            '#'.with(get_wall_clr())
         } else if !self.empt {
-            '.'.with(WALL_CLRS[flrs])
+            '.'.with(get_wall_clr())
         } else {
             ' '.stylize()
         }
@@ -298,6 +365,9 @@ pub enum ActionType {
     ForceMelee(Point, usize),
     /// Use the ranged attack at the given index.
     Fire(usize),
+    /// Use the ranged attack at the given index against the position at the given displacement,
+    /// if it is in range and nothing is in the way.
+    FireAt(usize, Point),
     /// Go to the furthest valid tile from the player using the enemy's movement pattern, if the
     /// player is within the given range (using manhattan distance). Fails if there is no way to
     /// get further from the player in one move.
@@ -337,7 +407,7 @@ pub enum ActionType {
 impl fmt::Display for ActionType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let txt = match self {
-            Self::TryMove(disp) => &tile_presets::ARROWS[disp.dir()].to_string(),
+            Self::TryMove(disp) => &glyphs().arrows[disp.dir()].to_string(),
             Self::TryMelee | Self::ForceMelee(_, _) => "A",
             Self::Pathfind => "P",
             Self::Wait => "W",
@@ -349,6 +419,7 @@ impl fmt::Display for ActionType {
             Self::Bridge(a, b) => &format!("B({}{})", a, b),
             Self::Repeat(a) => &format!("R({a})"),
             Self::Jump(idx) => &format!("J{idx}"),
+            Self::Fire(idx) | Self::FireAt(idx, _) => &format!("*{idx}"),
             _ => "?",
         };
 
@@ -0,0 +1,154 @@
+//! Full screen viewer for everything that has been logged during the current run.
+
+use crate::{Point, bn::windowed, print_win, save_file};
+use crate::entity::{LOG_MSGS, LogMsg};
+use crate::layout::Layout;
+use crossterm::style::{self, Stylize};
+use crossterm::{event, execute, terminal};
+use std::io;
+
+type StyleCh = style::StyledContent<char>;
+
+/// Rows at the top of the screen used by the title and the controls.
+const HEADER_HGT: usize = 3;
+/// Rows at the bottom of the screen used by the status line.
+const FOOTER_HGT: usize = 2;
+
+/// Return every message prefixed with the floor it happened on, as written to the exported file.
+fn export_lines(header: &str, msgs: &[LogMsg]) -> Vec<String> {
+    std::iter::once(String::from(header))
+        .chain(msgs.iter().map(|m| format!("Floor {}, {m}", m.floor())))
+        .collect()
+}
+
+/// Turn some text into a line of the given colour that is exactly wid characters long.
+fn line(clr: style::Color, txt: &str, wid: usize) -> Vec<StyleCh> {
+    let mut ln: Vec<StyleCh> = std::iter::once(' '.stylize())
+        .chain(txt.chars().map(|ch| ch.with(clr)))
+        .take(wid)
+        .collect();
+    ln.resize(wid, ' '.stylize());
+    ln
+}
+
+/// Show the log of the whole run until the player closes it. The header is shown at the top
+/// and written at the start of the exported file. The screen is cleared afterwards, so
+/// anything that was being shown needs printing again.
+pub fn view_log(header: &str) {
+    let msgs = LOG_MSGS.read().unwrap().clone();
+
+    // Every floor that has something logged, to filter by.
+    let mut floors: Vec<u32> = msgs.iter().map(|m| m.floor()).collect();
+    floors.dedup();
+
+    let mut lay = Layout::wait_for_fit();
+    let mut cont = windowed::Container::new();
+    cont.add_win(windowed::Window::new(Point::ORIGIN));
+    let mut handle = io::stdout();
+    let _ = execute!(handle, terminal::Clear(terminal::ClearType::All));
+
+    // Index into floors of the floor being shown, or None for all of them.
+    let mut filter: Option<usize> = None;
+    // Lines scrolled up from the bottom.
+    let mut scroll = 0;
+    let mut status = String::new();
+
+    loop {
+        let wid = lay.wid as usize;
+        let rows = (lay.hgt as usize).saturating_sub(HEADER_HGT + FOOTER_HGT).max(1);
+
+        let shown: Vec<&LogMsg> = msgs
+            .iter()
+            .filter(|m| filter.is_none_or(|f| m.floor() == floors[f]))
+            .collect();
+        let max_scroll = shown.len().saturating_sub(rows);
+        scroll = scroll.min(max_scroll);
+        let end = shown.len() - scroll;
+        let start = end.saturating_sub(rows);
+
+        let floor_txt = match filter {
+            Some(f) => format!("floor {}", floors[f]),
+            None => String::from("all floors"),
+        };
+
+        let data = &mut cont.windows[0].data;
+        data.clear();
+        data.push(line(style::Color::White, &format!("LOG: {header}, {floor_txt}"), wid));
+        data.push(line(
+            style::Color::DarkGrey,
+            "Up/Down: scroll  Left/Right: floor  x: export  Esc: close",
+            wid,
+        ));
+        data.push(line(style::Color::White, "", wid));
+        for msg in &shown[start..end] {
+            data.push(line(msg.clr(), &msg.to_string(), wid));
+        }
+        for _ in end - start..rows {
+            data.push(line(style::Color::White, "", wid));
+        }
+        data.push(line(style::Color::White, "", wid));
+        data.push(line(style::Color::Green, &status, wid));
+
+        cont.refresh();
+        print_win(&cont);
+
+        let ke = match event::read().expect("what") {
+            event::Event::Key(ke) if ke.is_press() => ke,
+            event::Event::Mouse(me) => {
+                match me.kind {
+                    event::MouseEventKind::ScrollUp => scroll = (scroll + 1).min(max_scroll),
+                    event::MouseEventKind::ScrollDown => scroll = scroll.saturating_sub(1),
+                    _ => (),
+                }
+                continue;
+            }
+            event::Event::Resize(..) => {
+                lay = Layout::wait_for_fit();
+                cont = windowed::Container::new();
+                cont.add_win(windowed::Window::new(Point::ORIGIN));
+                let _ = execute!(handle, terminal::Clear(terminal::ClearType::All));
+                continue;
+            }
+            _ => continue,
+        };
+
+        match ke.code {
+            event::KeyCode::Up | event::KeyCode::Char('w') | event::KeyCode::Char('k') => {
+                scroll = (scroll + 1).min(max_scroll);
+            }
+            event::KeyCode::Down | event::KeyCode::Char('s') | event::KeyCode::Char('j') => {
+                scroll = scroll.saturating_sub(1);
+            }
+            event::KeyCode::PageUp => scroll = (scroll + rows).min(max_scroll),
+            event::KeyCode::PageDown => scroll = scroll.saturating_sub(rows),
+            event::KeyCode::Home => scroll = max_scroll,
+            event::KeyCode::End => scroll = 0,
+            event::KeyCode::Left | event::KeyCode::Char('a') | event::KeyCode::Char('h') => {
+                filter = match filter {
+                    None => floors.len().checked_sub(1),
+                    Some(0) => None,
+                    Some(f) => Some(f - 1),
+                };
+                scroll = 0;
+            }
+            event::KeyCode::Right | event::KeyCode::Char('d') | event::KeyCode::Char('l') => {
+                filter = match filter {
+                    None if !floors.is_empty() => Some(0),
+                    Some(f) if f + 1 < floors.len() => Some(f + 1),
+                    _ => None,
+                };
+                scroll = 0;
+            }
+            event::KeyCode::Char('x') => {
+                status = match save_file::save_log(&export_lines(header, &msgs)) {
+                    Ok(path) => format!("Saved to {}", path.display()),
+                    Err(why) => format!("Couldn't save the log: {why}"),
+                };
+            }
+            event::KeyCode::Esc | event::KeyCode::Char('m') => break,
+            _ => (),
+        }
+    }
+
+    let _ = execute!(handle, terminal::Clear(terminal::ClearType::All));
+}
@@ -0,0 +1,223 @@
+//! Describes what is on a tile of the map, for the look mode.
+
+use crate::{Mechanism, Point, REVEALED, Tile, accuracy, fov, glyphs, tile_presets};
+use crate::bn;
+use crate::entity::{En, KEY_CLRS};
+use crossterm::style::{self, Stylize};
+use std::collections::HashMap;
+
+type StyleCh = style::StyledContent<char>;
+
+/// Width and height of the grid showing where an entity can attack and move.
+pub const FOOTPRINT_WID: usize = 7;
+/// Background colour of the tiles an entity can move to.
+const MOVE_CLR: style::Color = style::Color::Rgb {
+    r: 255,
+    g: 190,
+    b: 0,
+};
+
+/// Turn some text into a line of the given colour, with a space at the start to match the
+/// other windows.
+fn line(clr: style::Color, txt: &str) -> Vec<StyleCh> {
+    std::iter::once(' '.stylize())
+        .chain(txt.chars().map(|ch| ch.with(clr)))
+        .collect()
+}
+
+/// Describe everything at the given position, one line at a time.
+pub fn describe(map: &bn::Map<En>, pos: Point) -> Vec<Vec<StyleCh>> {
+    let see_all = *REVEALED.read().unwrap();
+    let tile = map.get_map(pos);
+
+    if !tile.is_some_and(|t| t.revealed) && !see_all {
+        return vec![line(style::Color::DarkGrey, "Unexplored")];
+    }
+
+    let mut lines = Vec::new();
+
+    if let Some(en) = map.get_ent(pos)
+        && ((!en.dormant && fov::visible(pos)) || see_all)
+    {
+        lines.extend(describe_entity(map, en, pos));
+        lines.push(Vec::new());
+    }
+
+    if let Some(t) = tile {
+        lines.extend(describe_tile(t));
+    }
+
+    lines
+}
+
+/// Describe an entity at the given position, including a grid of where it can attack and move.
+pub fn describe_entity(map: &bn::Map<En>, en: &En, pos: Point) -> Vec<Vec<StyleCh>> {
+    let mut lines = Vec::new();
+
+    let mut name = vec![' '.stylize(), en.ch, ' '.stylize()];
+    let kind = if en.is_player {
+        String::from("(you)")
+    } else {
+        format!("({})", en.special)
+    };
+    name.extend(kind.chars().map(|ch| ch.stylize()));
+    lines.push(name);
+
+    lines.push(line(
+        style::Color::Red,
+        &format!("HP: {}/{}", en.hp.value(), en.hp.max),
+    ));
+
+    if en.dormant {
+        lines.push(line(style::Color::DarkGrey, "Asleep"));
+    }
+
+    for st in &en.statuses {
+        lines.push(line(st.kind.clr(), &st.to_string()));
+    }
+    if accuracy::target_mod(en) > 0.0 {
+        lines.push(line(style::Color::White, "Easy to hit"));
+    }
+
+    for (kind, resist) in en.resist.unusual() {
+        lines.push(line(style::Color::White, &format!("{resist}: {kind}")));
+    }
+
+    if !en.actions.is_empty() {
+        let next = &en.actions[en.count % en.actions.len()];
+        if en.held() {
+            lines.push(line(style::Color::DarkGrey, "Next: nothing"));
+        } else {
+            lines.push(line(style::Color::White, &format!("Next: {next}")));
+        }
+    }
+
+    // Chance of its attacks hitting, counting whatever is affecting it right now.
+    let change = accuracy::attacker_mod(map, en, pos, en.vel.is_some());
+    if !en.atks.melee_atks.is_empty() {
+        let chance = accuracy::chance(en.atks.melee_acc(), change);
+        lines.push(line(
+            style::Color::White,
+            &format!("Accuracy: {}", accuracy::percent(chance)),
+        ));
+    }
+
+    for atk in &en.atks.ranged_atks {
+        let chance = accuracy::chance(atk.acc(), change);
+        lines.push(line(
+            style::Color::White,
+            &format!("{}: {} tiles, {}", atk.name, atk.range, accuracy::percent(chance)),
+        ));
+    }
+
+    lines.extend(footprint(en));
+    lines.push(line(style::Color::White, "Red: damage"));
+    let mut legend = vec![' '.stylize(), ' '.on(MOVE_CLR)];
+    legend.extend(": moves".chars().map(|ch| ch.stylize()));
+    lines.push(legend);
+
+    lines
+}
+
+/// Return a grid showing the damage the entity can do around it, with the tiles it can move to
+/// highlighted.
+pub fn footprint(en: &En) -> Vec<Vec<StyleCh>> {
+    let damages: HashMap<Point, i32> = en.atks.damage_map(Point::ORIGIN);
+    let centre = Point::new((FOOTPRINT_WID / 2) as i32, (FOOTPRINT_WID / 2) as i32);
+    let mut grid = Vec::new();
+
+    for y in 0..FOOTPRINT_WID {
+        let mut row = vec![' '.stylize()];
+        for x in 0..FOOTPRINT_WID {
+            let pos = Point::new(x as i32, y as i32);
+
+            let mut ch = '.'.stylize();
+            if pos == centre {
+                ch = en.ch;
+            } else if let Some(&dmg) = damages.get(&(pos - centre)) {
+                // Only a single hex digit fits.
+                let digit = char::from_digit(dmg.unsigned_abs().min(15), 16).unwrap();
+                ch = if dmg >= 0 { digit.red() } else { digit.green() };
+            }
+            if en.movement.contains(&(pos - centre)) {
+                if *ch.content() == '.' {
+                    ch = ' '.stylize();
+                }
+                ch = ch.on(MOVE_CLR);
+            }
+            row.push(ch);
+        }
+        grid.push(row);
+    }
+
+    grid
+}
+
+/// Describe a tile.
+pub fn describe_tile(t: &Tile) -> Vec<Vec<StyleCh>> {
+    let mut lines = Vec::new();
+
+    let name = if t.locked.is_some() {
+        "Locked door"
+    } else if let Some(m) = t.mechanism {
+        match m {
+            Mechanism::Spikes => "Spike trap",
+            Mechanism::Plate(_) | Mechanism::Latch(_) => "Pressure plate",
+            Mechanism::Gate(_) if t.blocking => "Closed gate",
+            Mechanism::Gate(_) => "Open gate",
+            Mechanism::Teleporter(_) => "Teleporter",
+            Mechanism::Cracked => "Cracked wall",
+        }
+    } else if t.door {
+        "Door"
+    } else if t.blocking {
+        "Wall"
+    } else if t.empt {
+        "Nothing"
+    } else {
+        "Floor"
+    };
+    lines.push(line(style::Color::White, name));
+
+    if let Some(k) = t.locked {
+        let clr = KEY_CLRS[k as usize % KEY_CLRS.len()];
+        let mut key_line = line(style::Color::White, "Needs key ");
+        key_line.push(glyphs().key.with(clr));
+        if t.unlockable() {
+            key_line.extend(" (held)".chars().map(|ch| ch.stylize()));
+        }
+        lines.push(key_line);
+    }
+
+    if t.slippery {
+        lines.push(line(style::Color::Cyan, "Slippery"));
+    }
+
+    match t.mechanism {
+        Some(Mechanism::Spikes) => {
+            lines.push(line(style::Color::White, &format!("Does {} damage", tile_presets::SPIKE_DMG)));
+        }
+        Some(Mechanism::Plate(_)) => {
+            lines.push(line(style::Color::White, "Opens a gate while held"));
+        }
+        Some(Mechanism::Latch(Some(_))) => {
+            lines.push(line(style::Color::White, "Opens a gate for good"));
+        }
+        Some(Mechanism::Gate(_)) => {
+            lines.push(line(style::Color::White, "Opened by a pressure plate"));
+        }
+        Some(Mechanism::Teleporter(Some(_))) => {
+            lines.push(line(style::Color::White, "Leads to another teleporter"));
+        }
+        Some(Mechanism::Cracked) => {
+            lines.push(line(style::Color::White, "Explosions destroy it"));
+        }
+        _ => (),
+    }
+
+    if t.step_effect.is_some() {
+        lines.push(line(style::Color::White, "Triggers when stepped on"));
+    }
+
+    lines
+}
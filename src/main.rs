@@ -14,8 +14,13 @@ use std::{collections::HashMap, fs, io, thread, time};
 use std::sync::LazyLock;
 use tile_presets::*;
 use untitled_bandit::*;
+use layout::*;
+use modifiers::{MODIFIERS, Modifier, Modifiers};
+use classes::{CLASS, Class};
 
 // UI constants.
+/// Background of the tiles an attack being aimed passes through.
+const AIM_CLR: style::Color = style::Color::DarkCyan;
 const SELECTOR: &str = ">";
 const SELECTOR_CLR: style::Color = style::Color::Rgb {
     r: 255,
@@ -27,34 +32,18 @@ const HOVER_CLR: style::Color = style::Color::Rgb {
     g: 240,
     b: 0,
 };
-const WINDOW_WIDTH: u16 = 40;
-const WINDOW_HEIGHT: u16 = 20;
 
 // All constants below describe the index of the window container that
-// the corresponding window is located at, or things about the window.
+// the corresponding window is located at. Positions and sizes are in the layout.
 const GAME: usize = 0;
 const STATS: usize = 1;
-const STATS_POS: Point = Point::new(22, 4);
-const STATS_WID: usize = 15;
 const ATKS: usize = 2;
-const ATKS_POS: Point = Point::new(32, 12);
-const ATKS_WID: usize = 5;
 const KEYS: usize = 3;
-const KEYS_POS: Point = Point::new(83, 4);
-const KEYS_WID: usize = KEY_CLRS.len() * 4 + 1;
 const LOG: usize = 4;
-const LOG_POS: Point = Point::new(83, 10);
-const LOG_WID: usize = 29;
-const LOG_HGT: usize = 11;
 const DEBUG_WIN: usize = 5;
-const DEBUG_POS: Point = Point::new(13, 19);
-const DEBUG_WID: usize = 24;
 const SEED_WIN: usize = 6;
-const SEED_POS: Point = Point::new(13, 19);
-const SEED_WID: usize = 24;
 const PUZZLE_WIN: usize = 7;
-const PUZZLE_WID: usize = 16;
-const PUZZLE_POS: Point = Point::new(TERMINAL_WID as i32 / 2 - PUZZLE_WID as i32 / 2, 0);
+const ITEMS: usize = 8;
 
 // Events for the ui.
 const QUIT: u32 = 0;
@@ -65,24 +54,51 @@ const PLAY_SEEDED: u32 = 4;
 const PUZZLE_SELECT: u32 = 5;
 const NEXT_PUZZLE: u32 = 6;
 const END_RUN: u32 = 7;
+const VIEW_LOG: u32 = 8;
+const PLAY_ENDLESS: u32 = 9;
+/// The first of the exit codes used by the upgrade draft, one for each upgrade offered.
+const UPGRADE: u32 = 10;
+/// The first of the exit codes used by the class screen, one for each class.
+const CHOOSE_CLASS: u32 = 20;
+
+/// Max health the player gives up to transcend, and gets back once past floor 3.
+const TRANSCEND_HP: u32 = 4;
+/// Mixed into the seed for the upgrade draft's rng, so drafts don't repeat the floor rng.
+const DRAFT_SALT: u64 = 0x5EED_D2AF_7000_0001;
 
 // Seed.
 static mut SEED: u64 = 0xFBAB693BEEFD53E4;
+/// Tile being inspected in look mode, if it is active.
+static mut LOOK: Option<Point> = None;
+/// Index of the ranged attack being aimed at the position being looked at, if there is one.
+static mut AIM: Option<usize> = None;
+/// First direction of a special move that is waiting for its second, if one has been started.
+static mut SPECIAL: Option<Point> = None;
 
 // Whether this here initial seed should be ignored.
 const SEED_OVERRIDE: bool = !CHEATS;
 
 fn main() {
+    // Run a command instead of the game if one was given.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
+
     // Get the path to this executable so that assets can be loaded even if the project is
     // downloaded from github.
     let this_path = get_assets_path();
 
+    // Load the settings before the screen is taken over, so any problem with them can be seen.
+    LazyLock::force(&settings::SETTINGS);
+
     // Raw mode required for windowed to work correctly.
     terminal::enable_raw_mode();
     execute!(
         io::stdout(),
         terminal::Clear(terminal::ClearType::All),
         terminal::SetSize(TERMINAL_WID, TERMINAL_HGT),
+        event::EnableMouseCapture,
     );
 
     // Get entity templates.
@@ -115,6 +131,7 @@ fn main() {
         door: false,
         revealed: true,
         locked: None,
+        mechanism: None,
         slippery: false,
         step_effect: None,
     });
@@ -126,6 +143,7 @@ fn main() {
         door: false,
         revealed: true,
         locked: None,
+        mechanism: None,
         slippery: false,
         step_effect: None,
     };
@@ -139,6 +157,19 @@ fn main() {
         ..empty_t.clone()
     });
 
+    // Add the traps and mechanisms. Puzzles always write these with the ascii glyphs, and
+    // teleporters and pressure plates are linked up when the puzzle is loaded.
+    let mechanisms = [
+        (tile_presets::SPIKE_CHAR, tile_presets::get_spikes(true)),
+        (glyphs::ASCII.teleporter, tile_presets::get_teleporter(true, None)),
+        (tile_presets::PLATE_CHAR, tile_presets::get_plate(true, None)),
+        (glyphs::ASCII.gate, tile_presets::get_gate(true, None)),
+        (glyphs::ASCII.cracked_wall, tile_presets::get_cracked_wall(true)),
+    ];
+    for (ch, t) in mechanisms {
+        tile_set.insert(ch, puzzle_loader::ts::BanditObj::Tile(t));
+    }
+
     // Load all enemy descriptions.
     let descs = templates::metadata::get_descs();
 
@@ -176,6 +207,24 @@ fn main() {
         },
     };
 
+    // Load the endless mode leaderboard.
+    let mut endless_records = match save_file::load_endless() {
+        Ok(records) => records,
+        Err(why) => match why {
+            puzzle_loader::LoadErr::NotFound => Vec::new(),
+            _ => panic!("{why}"),
+        },
+    };
+
+    // Load the best score of each class.
+    let mut class_scores = match save_file::load_class_scores() {
+        Ok(scores) => scores,
+        Err(why) => match why {
+            puzzle_loader::LoadErr::NotFound => HashMap::new(),
+            _ => panic!("{why}"),
+        },
+    };
+
     // Load whether we have won yet.
     let mut won_yet = match save_file::load_won() {
         Ok(won) => won,
@@ -191,16 +240,86 @@ fn main() {
 
     // Display the current state of the map into the terminal.
     let display_map =
-        |map: &bn::Map<En>, win_cont: &mut windowed::Container<style::StyledContent<char>>, stars_earned: &HashMap<u128, u8>| {
+        |map: &bn::Map<En>,
+         win_cont: &mut windowed::Container<style::StyledContent<char>>,
+         stars_earned: &HashMap<u128, u8>,
+         lay: &Layout,
+         cam: &mut camera::Camera| {
             let player_pos = unsafe { PLAYER };
             let pl = map.get_ent(player_pos).unwrap();
             let is_puzzle = unsafe { PUZZLE.is_some() };
 
             // Display the game window.
-            let top_left =
-                player_pos - Point::new(WINDOW_WIDTH as i32 / 2, -(WINDOW_HEIGHT as i32) / 2);
+            let top_left = cam.update(map, player_pos, lay.view_wid, lay.view_hgt);
             let mut cur_win = &mut win_cont.windows[GAME];
-            map.display_into(cur_win, top_left, WINDOW_WIDTH as u32, WINDOW_HEIGHT as u32);
+            map.display_into(cur_win, top_left, lay.view_wid, lay.view_hgt);
+            // Dim everything remembered but out of sight, hiding whatever is there now.
+            if !is_puzzle && !*REVEALED.read().unwrap() {
+                let seen = fov::VISIBLE.read().unwrap();
+                for (y, row) in cur_win.data.iter_mut().enumerate() {
+                    for (x, ch) in row.iter_mut().enumerate() {
+                        let p = cam.view_to_map(Point::new(x as i32, y as i32), lay.view_wid, lay.view_hgt);
+                        if seen.contains(&p) {
+                            continue;
+                        }
+                        *ch = fov::remembered(map, p);
+                    }
+                }
+            }
+            // Shade everything enemies can hit next turn.
+            if *threat::SHOW_THREATS.read().unwrap() {
+                let threats = threat::ThreatMap::new(map);
+                for &p in threats.tiles.keys() {
+                    if let Some(off) = cam.map_to_view(p, lay.view_wid, lay.view_hgt)
+                        && let Some(ch) = cur_win
+                            .data
+                            .get_mut(off.y as usize)
+                            .and_then(|row| row.get_mut(off.x as usize))
+                    {
+                        *ch = threats.shade(p, *ch, map.get_ent(p).is_some());
+                    }
+                }
+            }
+            // Only the room the player is in can be seen. Rooms they have been in before are
+            // remembered like anything else out of sight, and the rest is hidden.
+            if modifiers::active(Modifier::Fog) && !*REVEALED.read().unwrap() {
+                let here: Vec<_> = cam.rooms().iter().filter(|r| r.contains(player_pos)).collect();
+                for (y, row) in cur_win.data.iter_mut().enumerate() {
+                    for (x, ch) in row.iter_mut().enumerate() {
+                        let p = cam.view_to_map(Point::new(x as i32, y as i32), lay.view_wid, lay.view_hgt);
+                        if here.iter().any(|r| r.contains(p)) {
+                            continue;
+                        }
+                        *ch = if cam.entered(p) { fov::remembered(map, p) } else { ' '.stylize() };
+                    }
+                }
+            }
+            // Shade the tiles an attack being aimed would pass through.
+            if let Some(idx) = unsafe { AIM }
+                && let Some(target) = unsafe { LOOK }
+                && let Some(atk) = pl.atks.ranged_atks.get(idx)
+            {
+                for p in targeting::line_of_fire(map, player_pos, target, atk) {
+                    if let Some(off) = cam.map_to_view(p, lay.view_wid, lay.view_hgt)
+                        && let Some(ch) = cur_win
+                            .data
+                            .get_mut(off.y as usize)
+                            .and_then(|row| row.get_mut(off.x as usize))
+                    {
+                        *ch = ch.on(AIM_CLR);
+                    }
+                }
+            }
+            // Highlight the tile being looked at.
+            if let Some(look_pos) = unsafe { LOOK }
+                && let Some(off) = cam.map_to_view(look_pos, lay.view_wid, lay.view_hgt)
+                && let Some(ch) = cur_win
+                    .data
+                    .get_mut(off.y as usize)
+                    .and_then(|row| row.get_mut(off.x as usize))
+            {
+                *ch = ch.reverse();
+            }
             cur_win.outline_with('#'.grey());
 
             // Create some stats and put them in a window.
@@ -217,7 +336,9 @@ fn main() {
             );
             if unsafe { PUZZLE.is_none() } {
                 let flr = unsafe { 
-                    if FLOORS_CLEARED < 5 {
+                    if ENDLESS {
+                        DEPTH.to_string()
+                    } else if FLOORS_CLEARED < 5 {
                         FLOORS_CLEARED.to_string()
                     } else {
                         String::from("???")
@@ -245,6 +366,13 @@ fn main() {
                 cur_win,
                 STATS_WID,
             );
+            // Attacks that have hit out of all attacks at enemies.
+            add_line(
+                style::Color::Blue,
+                &format!("Hits: {}/{}", unsafe { HITS }, unsafe { HITS + MISSES }),
+                cur_win,
+                STATS_WID,
+            );
 
             cur_win.data.push(vec![' '.stylize(); STATS_WID]);
             cur_win.outline_with('#'.grey());
@@ -253,6 +381,16 @@ fn main() {
             cur_win = &mut win_cont.windows[ATKS];
             cur_win.data.clear();
             let damages: HashMap<Point, i32> = pl.atks.damage_map(Point::ORIGIN);
+            let hit_chance = accuracy::chance(
+                pl.atks.melee_acc(),
+                accuracy::attacker_mod(map, pl, player_pos, pl.vel.is_some()),
+            );
+            // Attacks that might miss are shown in a different colour.
+            let dmg_clr = if hit_chance < 1.0 {
+                style::Color::Yellow
+            } else {
+                style::Color::Red
+            };
 
             let win_centre = Point::new((ATKS_WID / 2) as i32, (ATKS_WID / 2) as i32);
 
@@ -266,7 +404,7 @@ fn main() {
                         ch = pl.ch;
                     } else if let Some(&dmg) = damages.get(&(pos - win_centre)) {
                         ch = if dmg >= 0 {
-                            char::from_digit(dmg as u32, 16).unwrap().red()
+                            char::from_digit(dmg as u32, 16).unwrap().with(dmg_clr)
                         } else {
                             char::from_digit(-dmg as u32, 16).unwrap().green()
                         };
@@ -274,6 +412,13 @@ fn main() {
                     cur_win.data[y].push(ch);
                 }
             }
+            // Chance of the attacks hitting, below the grid.
+            cur_win.data.push(
+                format!("{:>ATKS_WID$}", accuracy::percent(hit_chance))
+                    .chars()
+                    .map(|ch| ch.with(dmg_clr))
+                    .collect(),
+            );
 
             cur_win.outline_with('#'.grey());
 
@@ -289,7 +434,7 @@ fn main() {
                 let keys = unsafe { KEYS_COLLECTED[n] };
                 next_line.push(char::from_digit(keys, 16).unwrap().stylize());
                 next_line.push('x'.stylize());
-                next_line.push(KEY.with(if keys > 0 {
+                next_line.push(glyphs().key.with(if keys > 0 {
                     *clr
                 } else {
                     style::Color::DarkGrey
@@ -302,20 +447,68 @@ fn main() {
 
             cur_win.outline_with('#'.grey());
 
+            // Show the items the player is holding and the keys used to use them.
+            cur_win = &mut win_cont.windows[ITEMS];
+            cur_win.data.clear();
+            cur_win.data.push(vec![' '.stylize(); ITEMS_WID]);
+
+            add_line(style::Color::White, "ITEMS:", cur_win, ITEMS_WID);
+            let inv = items::INVENTORY.read().unwrap();
+            for slot in 0..items::MAX_ITEMS {
+                match inv.get(slot) {
+                    Some(item) => {
+                        let mut line = vec![' '.stylize()];
+                        line.extend(format!("{} ", slot + 1).chars().map(|ch| ch.stylize()));
+                        line.push(item.ch());
+                        line.extend(format!(" {item}").chars().map(|ch| ch.stylize()));
+                        line.resize(ITEMS_WID, ' '.stylize());
+                        cur_win.data.push(line);
+                    }
+                    None => add_line(style::Color::DarkGrey, &format!("{} -", slot + 1), cur_win, ITEMS_WID),
+                }
+            }
+            drop(inv);
+            cur_win.data.push(vec![' '.stylize(); ITEMS_WID]);
+
+            cur_win.outline_with('#'.grey());
+
             // Tell the player the last few things that have occurred.
             cur_win = &mut win_cont.windows[LOG];
             cur_win.data.clear();
             cur_win.data.push(vec![' '.stylize(); LOG_WID]);
-            add_line(style::Color::White, "LOG: ", cur_win, LOG_WID);
-            let read = LOG_MSGS.read().unwrap();
-            let len = read.len();
-            let start = len.saturating_sub(LOG_HGT);
-
-            for msg in LOG_MSGS.read().unwrap()[start..len].iter() {
-                add_line(style::Color::White, &msg.to_string(), cur_win, LOG_WID);
+            if let Some(look_pos) = unsafe { LOOK } {
+                // Describe whatever is being looked at instead of the log.
+                let header = match unsafe { AIM }.and_then(|idx| pl.atks.ranged_atks.get(idx)) {
+                    Some(atk) => {
+                        let change = accuracy::attacker_mod(map, pl, player_pos, false)
+                            + map.get_ent(look_pos).map_or(0.0, accuracy::target_mod);
+                        let chance = accuracy::percent(accuracy::chance(atk.acc(), change));
+                        format!("AIM: {} ({} tiles, {chance})", atk.name, atk.range)
+                    }
+                    None => String::from("LOOK: "),
+                };
+                add_line(style::Color::White, &header, cur_win, LOG_WID);
+                for mut line in look::describe(map, look_pos).into_iter().take(lay.log_lines + 1) {
+                    line.resize(LOG_WID, ' '.stylize());
+                    cur_win.data.push(line);
+                }
+            } else {
+                // Say that the game is waiting for the rest of a special move.
+                let header = if unsafe { SPECIAL }.is_some() { "SPECIAL: pick a direction" } else { "LOG: " };
+                add_line(style::Color::White, header, cur_win, LOG_WID);
+                let read = LOG_MSGS.read().unwrap();
+                // Only show what happened on this floor.
+                let floor = unsafe { DEPTH };
+                let msgs: Vec<&LogMsg> = read.iter().filter(|m| m.floor() == floor).collect();
+                let len = msgs.len();
+                let start = len.saturating_sub(lay.log_lines);
+
+                for msg in msgs[start..len].iter() {
+                    add_line(msg.clr(), &msg.to_string(), cur_win, LOG_WID);
+                }
             }
 
-            for _ in cur_win.data.len()..=LOG_HGT + 1 {
+            for _ in cur_win.data.len()..=lay.log_lines + 1 {
                 cur_win.data.push(vec![' '.stylize(); LOG_WID]);
             }
 
@@ -336,6 +529,18 @@ fn main() {
                         cur_win,
                         DEBUG_WID,
                     );
+                    add_line(
+                        style::Color::White,
+                        &format!("Mods: {}", mods_txt()),
+                        cur_win,
+                        DEBUG_WID,
+                    );
+                    add_line(
+                        style::Color::White,
+                        &format!("Class: {}", classes::current()),
+                        cur_win,
+                        DEBUG_WID,
+                    );
                     add_line(
                         style::Color::White,
                         &format!("Enemies: {}", unsafe { ENEMIES_REMAINING }),
@@ -371,6 +576,18 @@ fn main() {
                         cur_win,
                         SEED_WID,
                     );
+                    add_line(
+                        style::Color::White,
+                        &format!("Mods: {}", mods_txt()),
+                        cur_win,
+                        SEED_WID,
+                    );
+                    add_line(
+                        style::Color::White,
+                        &format!("Class: {}", classes::current()),
+                        cur_win,
+                        SEED_WID,
+                    );
 
                     cur_win.outline_with('#'.grey());
                 }
@@ -417,15 +634,21 @@ fn main() {
             GLOBAL_TIME = 0;
             COMBAT_TIME = 0;
             DAMAGE_DEALT = 0;
+            HITS = 0;
+            MISSES = 0;
             *CAN_F4.write().unwrap() = CHEATS;
 
             // Give a lot of keys on a debug build.
             let key_count = if CHEATS { 9 } else { 0 };
             KEYS_COLLECTED = [key_count; entity::KEY_CLRS_COUNT];
+            items::INVENTORY.write().unwrap().clear();
+            upgrades::TAKEN.write().unwrap().clear();
             LOG_MSGS.write().unwrap().clear();
             LAST_DOOR.write().unwrap().take();
             DEAD = false;
             FLOORS_CLEARED = 0;
+            DEPTH = 0;
+            themes::THEME = themes::Theme::Halls;
             NEXT_FLOOR = false;
             ENEMIES_REMAINING = 0;
             ACTION = ActionType::Wait;
@@ -438,9 +661,12 @@ fn main() {
         let delay = time::Duration::from_millis(DELAY);
         let vfx_delay = time::Duration::from_millis(VFX_DELAY);
         let mut ready;
+
+        // Work out where everything goes on the terminal.
+        let mut lay = Layout::wait_for_fit();
         
         // Main menu.
-        let mut scene = ui::Scene::new(Point::new(52, 20), 16, 7);
+        let mut scene = ui::Scene::new(Point::new(lay.centred(16), 20), 16, 11);
 
         if !quick_restart {
             // Clear the screen.
@@ -448,13 +674,14 @@ fn main() {
 
             unsafe {
                 PUZZLE = None;
+                ENDLESS = false;
             }
             // Open the main menu file.
             let mut f = fs::File::open(this_path.join("main_menu.txt")).unwrap();
             let mut main_text = String::new();
             f.read_to_string(&mut main_text);
 
-            let title = ui::widgets::Title::new(Point::new(26, 0), main_text, Some(delay));
+            let title = ui::widgets::Title::new(Point::new(lay.centred(text_wid(&main_text)), 0), main_text, Some(delay));
             scene.add_element(Box::new(title), Point::new(500, 500));
         }
 
@@ -488,12 +715,42 @@ fn main() {
             Box::new(
                 basic_button
                     .clone()
-                    .set_txt(String::from("Seeded Run"))
-                    .set_event(ui::Event::ChangeScene(1))
+                    .set_txt(String::from("Endless"))
+                    .set_event(ui::Event::Exit(PLAY_ENDLESS))
                     .set_screen_pos(Point::new(1, 2)),
             ),
             Point::new(1, 2),
         );
+        scene.add_element(
+            Box::new(
+                basic_button
+                    .clone()
+                    .set_txt(String::from("Seeded Run"))
+                    .set_event(ui::Event::ChangeScene(1))
+                    .set_screen_pos(Point::new(1, 3)),
+            ),
+            Point::new(1, 3),
+        );
+        scene.add_element(
+            Box::new(
+                basic_button
+                    .clone()
+                    .set_txt(String::from("Class"))
+                    .set_event(ui::Event::ChangeScene(8))
+                    .set_screen_pos(Point::new(1, 4)),
+            ),
+            Point::new(1, 4),
+        );
+        scene.add_element(
+            Box::new(
+                basic_button
+                    .clone()
+                    .set_txt(String::from("Modifiers"))
+                    .set_event(ui::Event::ChangeScene(7))
+                    .set_screen_pos(Point::new(1, 5)),
+            ),
+            Point::new(1, 5),
+        );
         scene.add_element(
             Box::new(
                 basic_button
@@ -503,9 +760,9 @@ fn main() {
                         ui::Event::Broadcast(String::from("clr")),
                         ui::Event::ChangeScene(3)
                     ])
-                    .set_screen_pos(Point::new(1, 3)),
+                    .set_screen_pos(Point::new(1, 6)),
             ),
-            Point::new(1, 3),
+            Point::new(1, 6),
         );
         scene.add_element(
             Box::new(
@@ -516,9 +773,19 @@ fn main() {
                         ui::Event::Broadcast(String::from("clr")),
                         ui::Event::ChangeScene(6)
                     ])
-                    .set_screen_pos(Point::new(1, 4)),
+                    .set_screen_pos(Point::new(1, 7)),
             ),
-            Point::new(1, 4),
+            Point::new(1, 7),
+        );
+        scene.add_element(
+            Box::new(
+                basic_button
+                    .clone()
+                    .set_txt(String::from("Records"))
+                    .set_event(ui::Event::ChangeScene(9))
+                    .set_screen_pos(Point::new(1, 8)),
+            ),
+            Point::new(1, 8),
         );
         scene.add_element(
             Box::new(
@@ -526,9 +793,9 @@ fn main() {
                     .clone()
                     .set_txt(String::from("Save and Quit"))
                     .set_event(ui::Event::Exit(QUIT))
-                    .set_screen_pos(Point::new(1, 5)),
+                    .set_screen_pos(Point::new(1, 9)),
             ),
-            Point::new(1, 5),
+            Point::new(1, 9),
         );
         scene.add_element(
             Box::new(ui::widgets::Outline::new('#'.grey(), 16)),
@@ -537,10 +804,10 @@ fn main() {
         scene.move_cursor(Point::new(1, 1));
         menu_container.add_scene(scene);
 
-        // Seed entry screen.
-        let seed_wid = 20;
+        // Seed entry screen. Takes either a seed or a run code.
+        let seed_wid = 23;
         let mut seed_scene = ui::Scene::new(
-            Point::new(TERMINAL_WID as i32 / 2 - seed_wid as i32 / 2, 20),
+            Point::new(lay.centred(seed_wid), 20),
             seed_wid,
             5,
         );
@@ -558,7 +825,7 @@ fn main() {
             Box::new(
                 basic_entry
                     .clone()
-                    .set_len(16)
+                    .set_len(19)
                     .set_screen_pos(Point::new(2, 2)),
             ),
             Point::new(1, 2),
@@ -592,7 +859,7 @@ fn main() {
         menu_container.add_scene(seed_scene);
 
         // Death / win_screen.
-        let mut end_scene = ui::Scene::new(Point::new(52, 22), 16, 5);
+        let mut end_scene = ui::Scene::new(Point::new(lay.centred(16), 25), 16, 6);
 
         end_scene.add_element(
             Box::new(
@@ -618,12 +885,22 @@ fn main() {
             Box::new(
                 basic_button
                     .clone()
-                    .set_txt(String::from("Save and Quit"))
-                    .set_event(ui::Event::Exit(QUIT))
+                    .set_txt(String::from("View Log"))
+                    .set_event(ui::Event::Exit(VIEW_LOG))
                     .set_screen_pos(Point::new(1, 3)),
             ),
             Point::new(1, 3),
         );
+        end_scene.add_element(
+            Box::new(
+                basic_button
+                    .clone()
+                    .set_txt(String::from("Save and Quit"))
+                    .set_event(ui::Event::Exit(QUIT))
+                    .set_screen_pos(Point::new(1, 4)),
+            ),
+            Point::new(1, 4),
+        );
         end_scene.add_element(
             Box::new(ui::widgets::Outline::new('#'.grey(), 16)),
             Point::new(999, 999),
@@ -633,14 +910,14 @@ fn main() {
         menu_container.add_scene(end_scene);
 
         // Puzzle selection screen.
-        let mut pzl_scene = ui::Scene::new(Point::new(49, 12), 22, 13).with_scrolling(true);
+        let mut pzl_scene = ui::Scene::new(Point::new(lay.centred(22), 12), 22, 13).with_scrolling(true);
         
         // Open the puzzle title file.
         let mut f = fs::File::open(this_path.join("puzzle_title.txt")).unwrap();
         let mut main_text = String::new();
         f.read_to_string(&mut main_text);
 
-        let title = ui::widgets::Title::new(Point::new(25, 1), main_text, Some(delay));
+        let title = ui::widgets::Title::new(Point::new(lay.centred(text_wid(&main_text)), 1), main_text, Some(delay));
         pzl_scene.add_element(Box::new(title), Point::new(500, 500));
 
         // Add an indicator for total stars collected.
@@ -756,7 +1033,7 @@ fn main() {
         menu_container.add_scene(pzl_scene);
 
         // Alternate end screen for puzzles.
-        let mut puzzle_end = ui::Scene::new(Point::new(52, 18), 16, 7);
+        let mut puzzle_end = ui::Scene::new(Point::new(lay.centred(16), 18), 16, 7);
         let next = basic_button
             .clone()
             .set_txt(String::from("Next Puzzle"))
@@ -827,7 +1104,7 @@ fn main() {
         menu_container.add_scene(dead_puzzle_end);
         
         // Alphabet screen.
-        let mut alpha_scene = ui::Scene::new(Point::new(43, 10), 34, 15).with_scrolling(true);
+        let mut alpha_scene = ui::Scene::new(Point::new(lay.centred(34), 10), 34, 15).with_scrolling(true);
         let mut info_boxes = ui::widgets::MultiBox::new(Point::new(10, 1), false);
 
         let kill_read = KILL_COUNTS.read().unwrap();
@@ -838,7 +1115,7 @@ fn main() {
         let mut main_text = String::new();
         f.read_to_string(&mut main_text);
 
-        let title = ui::widgets::Title::new(Point::new(20, 0), main_text, Some(delay));
+        let title = ui::widgets::Title::new(Point::new(lay.centred(text_wid(&main_text)), 0), main_text, Some(delay));
         alpha_scene.add_element(Box::new(title), Point::new(500, 500));
 
         // Default values for the null info box.
@@ -848,6 +1125,7 @@ fn main() {
             ch: '?'.stylize(),
             actions: Vec::new(),
             movement: Vec::new(),
+            resist: attacks::Resistances::NONE,
         };
         let def_meta = TempMeta {
             floor_rang: 0..=0,
@@ -949,6 +1227,53 @@ fn main() {
 
         menu_container.add_scene(alpha_scene);
 
+        // Modifiers screen.
+        let mods_wid = 28;
+        let mut mods_scene = ui::Scene::new(
+            Point::new(lay.centred(mods_wid), 19),
+            mods_wid,
+            Modifier::ALL.len() + 4,
+        );
+        let cur_mods = *MODIFIERS.read().unwrap();
+
+        for (n, m) in Modifier::ALL.into_iter().enumerate() {
+            let pos = Point::new(1, n as i32 + 1);
+            mods_scene.add_element(
+                Box::new(
+                    ui::widgets::Toggle::new(format!("{m} x{:.2}", m.score_mult()))
+                        .set_on(cur_mods.has(m))
+                        .set_selector(String::from(SELECTOR))
+                        .set_hover_clr(HOVER_CLR)
+                        .set_selector_clr(SELECTOR_CLR)
+                        .set_screen_pos(pos),
+                ),
+                pos,
+            );
+        }
+        mods_scene.add_element(
+            Box::new(
+                basic_button
+                    .clone()
+                    .set_txt(String::from("Back"))
+                    .set_event(ui::Event::ChangeScene(0))
+                    .set_screen_pos(Point::new(1, Modifier::ALL.len() as i32 + 2)),
+            ),
+            Point::new(1, Modifier::ALL.len() as i32 + 1),
+        );
+        mods_scene.add_element(
+            Box::new(ui::widgets::Outline::new('#'.grey(), mods_wid)),
+            Point::new(999, 999),
+        );
+
+        mods_scene.move_cursor(Point::new(1, 1));
+        menu_container.add_scene(mods_scene);
+
+        // Class select screen.
+        menu_container.add_scene(make_class_scene(&lay, &basic_button));
+
+        // Endless mode leaderboard.
+        menu_container.add_scene(make_records_scene(&lay, &basic_button, &endless_records));
+
         if insta_puzzle {
             menu_container.change_scene(3);
             insta_puzzle = false;
@@ -956,19 +1281,55 @@ fn main() {
 
         // Run the main menu ui if we aren't restarting quickly.
         if !quick_restart {
-            match menu_container.run() {
+            // Choosing a class goes back to the main menu, with the class screen showing the new
+            // class for next time.
+            let code = loop {
+                match menu_container.run() {
+                    c if c >= CHOOSE_CLASS && ((c - CHOOSE_CLASS) as usize) < Class::ALL.len() => {
+                        *CLASS.write().unwrap() = Class::ALL[(c - CHOOSE_CLASS) as usize];
+                        // The terminal may have been resized since the menus were made.
+                        lay = Layout::wait_for_fit();
+                        menu_container.scenes[8] = make_class_scene(&lay, &basic_button);
+                        let _ = execute!(handle, terminal::Clear(terminal::ClearType::All));
+                        menu_container.change_scene(0);
+                        menu_container.reprint();
+                    }
+                    c => break c,
+                }
+            };
+
+            // Use whatever was chosen on the modifiers screen.
+            let mut mods = Modifiers::NONE;
+            for (n, m) in Modifier::ALL.into_iter().enumerate() {
+                let on = menu_container.scenes[7]
+                    .get_element(Point::new(1, n as i32 + 1))
+                    .is_some_and(|e| e.get_text() == "yes");
+                mods.set(m, on);
+            }
+            *MODIFIERS.write().unwrap() = mods;
+
+            match code {
                 QUIT => break 'full,
                 PLAY => (),
+                PLAY_ENDLESS => unsafe {
+                    ENDLESS = true;
+                },
                 PLAY_SEEDED => unsafe {
                     let txt = &menu_container.scenes[1]
                         .get_element(Point::new(1, 2))
                         .unwrap()
                         .get_text();
-                    // Hash the string to get the seed if it is not in hex.
-                    SEED = match u64::from_str_radix(txt, 16) {
-                        Ok(val) => val,
-                        Err(_) => u64::from_ne_bytes(md5::compute(txt).0[0..8].try_into().unwrap()),
-                    };
+                    // Run codes also bring their modifiers with them. Otherwise, hash the string to
+                    // get the seed if it is not in hex.
+                    if let Some((seed, mods)) = Modifiers::from_run_code(txt) {
+                        SEED = seed;
+                        *MODIFIERS.write().unwrap() = mods;
+                    } else {
+                        SEED = match u64::from_str_radix(txt, 16) {
+                            Ok(val) => val,
+                            Err(_) => u64::from_ne_bytes(md5::compute(txt).0[0..8].try_into().unwrap()),
+                        };
+                    }
                 },
                 // Puzzle selected.
                 c if c >= 100 && c < 100 + pzl_count as u32 => unsafe {
@@ -983,24 +1344,24 @@ fn main() {
         // Time when the game began.
         let start = time::Instant::now();
 
-        // Create the various windows required for the main game.
-        let mut main_wins = windowed::Container::new();
-        let win_left = TERMINAL_WID / 2 - WINDOW_WIDTH / 2;
-        let win_top = TERMINAL_HGT / 2 - WINDOW_HEIGHT / 2 - 1;
-        main_wins.add_win(windowed::Window::new(Point::new(
-            win_left as i32,
-            win_top as i32,
-        )));
-        main_wins.add_win(windowed::Window::new(STATS_POS));
-        main_wins.add_win(windowed::Window::new(ATKS_POS));
-        main_wins.add_win(windowed::Window::new(KEYS_POS));
-        main_wins.add_win(windowed::Window::new(LOG_POS));
-        main_wins.add_win(windowed::Window::new(DEBUG_POS));
-        main_wins.add_win(windowed::Window::new(SEED_POS));
-        main_wins.add_win(windowed::Window::new(PUZZLE_POS));
+        // Create the various windows required for the main game. The terminal may have been
+        // resized while in the menus.
+        lay = Layout::wait_for_fit();
+        let mut main_wins = make_game_wins(&lay);
 
         // Seed the rng.
         let mut floor_rng = rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(unsafe { SEED });
+        // Drafts get an rng of their own, so the floors of a seed don't depend on the upgrades.
+        let mut draft_rng = rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(unsafe { SEED } ^ DRAFT_SALT);
+
+        // Decides which part of the map is visible.
+        let mut camera = camera::Camera::new(Point::ORIGIN);
+
+        // Journey the player is currently taking across the floor.
+        let mut travel = travel::Travel::new();
+
+        // Layout of the current floor.
+        let mut floor_info = FloorInfo::default();
 
         // Map used through the game.
         let mut map: bn::Map<En> = unsafe {
@@ -1008,12 +1369,13 @@ fn main() {
                 let pzl = &pzls[idx];
                 PLAYER = pzl.pl_pos;
                 ENEMIES_REMAINING = pzl.data.get_entities().count() - 1;
+                camera.set_floor(Vec::new(), PLAYER);
                 pzl.data.clone()
             } else {
                 let mut map = bn::Map::new(69, 69);
 
                 // Generate the initial floor.
-                gen_floor(
+                floor_info = gen_floor(
                     &mut map,
                     &mut floor_rng,
                     FLOORS_CLEARED,
@@ -1021,75 +1383,23 @@ fn main() {
                     &templates,
                     &elites,
                 );
+                camera.set_floor(floor_info.rooms.clone(), PLAYER);
 
                 map
             }
         };
 
         execute!(handle, terminal::Clear(terminal::ClearType::All));
-        display_map(&map, &mut main_wins, &stars_earned);
+        display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
         let mut score = 0.0;
         let mut f4_state = 0;
 
         'main: loop {
             ready = true;
 
-            let floor_win_len = 40;
+            let floor_win_len: usize = 40;
             let mut floor_end_wins = windowed::Container::new();
-            floor_end_wins.add_win(windowed::Window::new(Point::new((TERMINAL_WID / 2) as i32 - floor_win_len / 2, 12)));
-
-            let mut floor_end_ui = ui::UiContainer::new();
-
-            let mut scene = ui::Scene::new(Point::new(54, 20), 12, 5);
-
-            // Open the floor complete file.
-            let mut f = fs::File::open(this_path.join("floor_complete.txt")).unwrap();
-            let mut main_text = String::new();
-            f.read_to_string(&mut main_text);
-
-            let title = ui::widgets::Title::new(Point::new(2, 2), main_text, None);
-            scene.add_element(Box::new(title), Point::new(500, 500));
-
-            scene.add_element(
-                Box::new(
-                    basic_button
-                        .clone()
-                        .set_txt(String::from("Continue"))
-                        .set_events(vec![
-                            ui::Event::Broadcast(String::from("clr")),
-                            ui::Event::Exit(PLAY)
-                        ])
-                        .set_screen_pos(Point::new(1, 1)),
-                ),
-                Point::new(1, 1),
-            );
-            scene.add_element(
-                Box::new(
-                    basic_button
-                        .clone()
-                        .set_txt(String::from("End Run"))
-                        .set_event(ui::Event::Exit(END_RUN))
-                        .set_screen_pos(Point::new(1, 2)),
-                ),
-                Point::new(1, 2),
-            );
-            scene.add_element(
-                Box::new(
-                    basic_button
-                        .clone()
-                        .set_txt(String::from("Quit"))
-                        .set_event(ui::Event::Exit(QUIT))
-                        .set_screen_pos(Point::new(1, 3)),
-                ),
-                Point::new(1, 3),
-            );
-            scene.add_element(
-                Box::new(ui::widgets::Outline::new('#'.grey(), 12)),
-                Point::new(999, 999),
-            );
-            scene.move_cursor(Point::new(1, 1));
-
-            floor_end_ui.add_scene(scene);
+            floor_end_wins.add_win(windowed::Window::new(Point::new(lay.centred(floor_win_len), 12)));
 
             // Necessary to know what occurred on each floor.
             let mut killed = 0;
@@ -1098,8 +1408,61 @@ fn main() {
             let mut damage = 0;
             let mut last_floor = start;
 
-            if map.get_ent(unsafe { PLAYER }).unwrap().vel.is_none() {
-                while let event::Event::Key(ke) = event::read().expect("what") {
+            let pl_still = map.get_ent(unsafe { PLAYER }).unwrap().vel.is_none();
+
+            // Any key press stops the player travelling.
+            if pl_still && travel.is_travelling() {
+                while event::poll(time::Duration::from_secs(0)).unwrap_or(false) {
+                    match event::read() {
+                        Ok(event::Event::Key(ke)) if ke.is_press() => travel.stop(),
+                        Ok(event::Event::Resize(..)) => {
+                            lay = Layout::wait_for_fit();
+                            main_wins = make_game_wins(&lay);
+                            execute!(handle, terminal::Clear(terminal::ClearType::All));
+                            display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
+                        }
+                        _ => (),
+                    }
+                }
+            }
+
+            let travel_step = if pl_still { travel.step(&map) } else { None };
+
+            if let Some(disp) = travel_step {
+                // Pause between steps so the journey can be watched.
+                thread::sleep(delay);
+                unsafe {
+                    ACTION = ActionType::TryMove(disp);
+                }
+            } else if pl_still {
+                loop {
+                    let ke = match event::read().expect("what") {
+                        event::Event::Key(ke) => ke,
+                        event::Event::Resize(..) => {
+                            // Reflow all the windows to fit the new size.
+                            lay = Layout::wait_for_fit();
+                            main_wins = make_game_wins(&lay);
+                            execute!(handle, terminal::Clear(terminal::ClearType::All));
+                            display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
+                            continue;
+                        }
+                        // Clicking on a tile travels there.
+                        event::Event::Mouse(me) => {
+                            if let event::MouseEventKind::Down(event::MouseButton::Left) = me.kind
+                                && let Some(off) = lay.view_offset(me.column, me.row)
+                                && travel.start(&map, camera.view_to_map(off, lay.view_wid, lay.view_hgt))
+                                && let Some(disp) = travel.step(&map)
+                            {
+                                unsafe {
+                                    ACTION = ActionType::TryMove(disp);
+                                }
+                                break;
+                            }
+                            continue;
+                        }
+                        _ => continue,
+                    };
+
                     if ke.is_press() {
                         let action = match ke.code {
                             // Has arrow keys, wasd, and, for the vim users among us, hjkl.
@@ -1115,6 +1478,47 @@ fn main() {
                             event::KeyCode::Up
                             | event::KeyCode::Char('w')
                             | event::KeyCode::Char('k') => ActionType::TryMove(Point::new(0, 1)),
+                            // Start a special move with shift, then pick the second direction.
+                            event::KeyCode::Char(c @ ('A' | 'D' | 'S' | 'W' | 'H' | 'J' | 'K' | 'L')) => {
+                                let first = dir_from_key(event::KeyCode::Char(c.to_ascii_lowercase())).unwrap();
+                                unsafe {
+                                    SPECIAL = Some(first);
+                                }
+                                display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
+
+                                let second = loop {
+                                    match event::read().expect("Couldn't read from the terminal") {
+                                        event::Event::Key(ke) if ke.is_press() => match ke.code {
+                                            event::KeyCode::Esc => break None,
+                                            event::KeyCode::Char(c) => {
+                                                break dir_from_key(event::KeyCode::Char(c.to_ascii_lowercase()))
+                                            }
+                                            code => break dir_from_key(code),
+                                        },
+                                        event::Event::Resize(..) => {
+                                            lay = Layout::wait_for_fit();
+                                            main_wins = make_game_wins(&lay);
+                                            execute!(handle, terminal::Clear(terminal::ClearType::All));
+                                            display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
+                                        }
+                                        _ => (),
+                                    }
+                                };
+
+                                unsafe {
+                                    SPECIAL = None;
+                                }
+                                let pos = unsafe { PLAYER };
+                                match second.and_then(|second| {
+                                    classes::special_move(&map, map.get_ent(pos).unwrap(), pos, first, second)
+                                }) {
+                                    Some(disp) => ActionType::TryMove(disp),
+                                    None => {
+                                        display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
+                                        continue;
+                                    }
+                                }
+                            }
                             event::KeyCode::Char('.') => ActionType::Wait,
                             // Skip to next floor.
                             event::KeyCode::Char('n') => unsafe {
@@ -1136,7 +1540,7 @@ fn main() {
                                     *NO_CLIP.write().unwrap() = !clipping;
 
                                     let mut write = LOG_MSGS.write().unwrap();
-                                    write.push(LogMsg::new(format!(
+                                    write.push(LogMsg::from(format!(
                                         "{} {}s hacking",
                                         templates::PLAYER_CHARACTER,
                                         if clipping { "stop" } else { "start" }
@@ -1153,7 +1557,7 @@ fn main() {
                                     *REVEALED.write().unwrap() = !rev;
 
                                     let mut write = LOG_MSGS.write().unwrap();
-                                    write.push(LogMsg::new(format!(
+                                    write.push(LogMsg::from(format!(
                                         "{} {}s seeing all",
                                         templates::PLAYER_CHARACTER,
                                         if rev { "stop" } else { "start" }
@@ -1171,11 +1575,6 @@ fn main() {
                                 quick_restart = true;
                                 continue 'full;
                             },
-                            #[cfg(debug_assertions)]
-                            event::KeyCode::Char('v') => {
-                                check_seeds(0x5F19E7B2F1F16EAB, 10);
-                                ActionType::Wait
-                            }
                             // Kill everyone in the room.
                             event::KeyCode::Char('*') => unsafe {
                                 if CHEATS {
@@ -1195,12 +1594,12 @@ fn main() {
                                     }
 
                                     let mut write = LOG_MSGS.write().unwrap();
-                                    write.push(LogMsg::new(format!(
+                                    write.push(LogMsg::from(format!(
                                         "{} inquires about the",
                                         templates::PLAYER_CHARACTER
                                     )));
-                                    write.push(LogMsg::new(String::from("extended warranty of")));
-                                    write.push(LogMsg::new(String::from("the enemies' vehicles")));
+                                    write.push(LogMsg::from(String::from("extended warranty of")));
+                                    write.push(LogMsg::from(String::from("the enemies' vehicles")));
                                     ActionType::Wait
                                 } else {
                                     continue;
@@ -1219,6 +1618,19 @@ fn main() {
                                 f4_state += 1000000;
                                 ActionType::Wait
                             }
+                            // Use the item in one of the slots.
+                            event::KeyCode::Char(c) if items::slot_for_key(c).is_some() => {
+                                let slot = items::slot_for_key(c).unwrap();
+                                // Items that wouldn't do anything are kept, and no turn is taken.
+                                match items::get(slot) {
+                                    Some(item) if item.usable(&map, unsafe { PLAYER }) => {
+                                        items::take(slot);
+                                        LOG_MSGS.write().unwrap().push(LogEvent::ItemUsed(item).into());
+                                        item.action()
+                                    }
+                                    _ => continue,
+                                }
+                            }
                             event::KeyCode::Char('c') => {
                                 let mut write = SEE_HEALTH.write().unwrap();
                                 let old = *write;
@@ -1226,7 +1638,167 @@ fn main() {
 
                                 // Have to kill it so that enemies don't poison the rwlock.
                                 drop(write);
-                                display_map(&map, &mut main_wins, &stars_earned);
+                                display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
+                                continue;
+                            }
+                            // Look back through everything that has happened this run.
+                            event::KeyCode::Char('m') => {
+                                log_view::view_log(&log_header());
+                                lay = Layout::wait_for_fit();
+                                main_wins = make_game_wins(&lay);
+                                display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
+                                continue;
+                            }
+                            // Show a map of everything revealed on this floor.
+                            event::KeyCode::Char('o') => {
+                                overview::view_overview(&map, &floor_info);
+                                lay = Layout::wait_for_fit();
+                                main_wins = make_game_wins(&lay);
+                                display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
+                                continue;
+                            }
+                            // Show or hide everything enemies can hit next turn.
+                            event::KeyCode::Char('t') => {
+                                let mut write = threat::SHOW_THREATS.write().unwrap();
+                                let old = *write;
+                                *write = !old;
+
+                                drop(write);
+                                display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
+                                continue;
+                            }
+                            // Travel to the next door that leads somewhere unexplored.
+                            event::KeyCode::Char('g') => {
+                                if travel.next_door(&map, camera.rooms())
+                                    && let Some(disp) = travel.step(&map)
+                                {
+                                    ActionType::TryMove(disp)
+                                } else {
+                                    continue;
+                                }
+                            }
+                            // Move a cursor around the view to inspect entities and tiles.
+                            event::KeyCode::Char('e') => {
+                                unsafe {
+                                    LOOK = Some(PLAYER);
+                                }
+                                display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
+
+                                loop {
+                                    let look_pos = unsafe { LOOK.unwrap() };
+                                    let new_pos = match event::read().expect("what") {
+                                        event::Event::Key(ke) if ke.is_press() => match ke.code {
+                                            event::KeyCode::Esc | event::KeyCode::Char('e') => break,
+                                            code => match dir_from_key(code) {
+                                                Some(dir) => look_pos + dir,
+                                                None => continue,
+                                            },
+                                        },
+                                        event::Event::Mouse(me) => {
+                                            if let event::MouseEventKind::Moved = me.kind
+                                                && let Some(off) = lay.view_offset(me.column, me.row)
+                                            {
+                                                camera.view_to_map(off, lay.view_wid, lay.view_hgt)
+                                            } else {
+                                                continue;
+                                            }
+                                        }
+                                        event::Event::Resize(..) => {
+                                            lay = Layout::wait_for_fit();
+                                            main_wins = make_game_wins(&lay);
+                                            execute!(handle, terminal::Clear(terminal::ClearType::All));
+                                            look_pos
+                                        }
+                                        _ => continue,
+                                    };
+
+                                    // The cursor can't leave the view.
+                                    if camera.map_to_view(new_pos, lay.view_wid, lay.view_hgt).is_some() {
+                                        unsafe {
+                                            LOOK = Some(new_pos);
+                                        }
+                                    }
+                                    display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
+                                }
+
+                                unsafe {
+                                    LOOK = None;
+                                }
+                                display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
+                                continue;
+                            }
+                            // Aim a ranged attack, going through the targets in range.
+                            event::KeyCode::Char('q') => {
+                                let pos = unsafe { PLAYER };
+                                let pl = map.get_ent(pos).unwrap();
+                                // Every target of the first ranged attack, then the second and so on.
+                                let choices: Vec<(usize, Point)> = pl
+                                    .atks
+                                    .ranged_atks
+                                    .iter()
+                                    .enumerate()
+                                    .flat_map(|(idx, atk)| {
+                                        targeting::targets(&map, pos, atk).into_iter().map(move |t| (idx, t))
+                                    })
+                                    .collect();
+
+                                if choices.is_empty() {
+                                    let msg = if pl.atks.ranged_atks.is_empty() {
+                                        "Nothing to fire"
+                                    } else {
+                                        "Nothing in range"
+                                    };
+                                    LOG_MSGS.write().unwrap().push(LogMsg::from(String::from(msg)));
+                                    display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
+                                    continue;
+                                }
+
+                                let mut cur = 0;
+                                let chosen = loop {
+                                    unsafe {
+                                        AIM = Some(choices[cur].0);
+                                        LOOK = Some(choices[cur].1);
+                                    }
+                                    display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
+
+                                    match event::read().expect("what") {
+                                        event::Event::Key(ke) if ke.is_press() => match ke.code {
+                                            event::KeyCode::Char('q') | event::KeyCode::Enter => {
+                                                break Some(choices[cur]);
+                                            }
+                                            event::KeyCode::Esc => break None,
+                                            event::KeyCode::Tab => cur = (cur + 1) % choices.len(),
+                                            code => match dir_from_key(code) {
+                                                Some(dir) if dir.x + dir.y > 0 => cur = (cur + 1) % choices.len(),
+                                                Some(_) => cur = (cur + choices.len() - 1) % choices.len(),
+                                                None => (),
+                                            },
+                                        },
+                                        event::Event::Resize(..) => {
+                                            lay = Layout::wait_for_fit();
+                                            main_wins = make_game_wins(&lay);
+                                            execute!(handle, terminal::Clear(terminal::ClearType::All));
+                                        }
+                                        _ => (),
+                                    }
+                                };
+
+                                unsafe {
+                                    AIM = None;
+                                    LOOK = None;
+                                }
+                                match chosen {
+                                    Some((idx, target)) => ActionType::FireAt(idx, target - pos),
+                                    None => {
+                                        display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
+                                        continue;
+                                    }
+                                }
+                            }
+                            // Switch between following the player and showing their whole room.
+                            event::KeyCode::Char('z') => {
+                                camera.room_focus = !camera.room_focus;
+                                display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
                                 continue;
                             }
                             event::KeyCode::Char('r') => {
@@ -1285,22 +1857,26 @@ fn main() {
                     // Make the player red so they know they've done something good.
                     let pl = map.get_ent_mut(unsafe { PLAYER }).unwrap();
                     pl.ch = PLAYER_CHARACTER.with(style::Color::Red);
-                    pl.hp.change_max(5);
-                    LOG_MSGS.write().unwrap().push(LogMsg::new(format!("{PLAYER_CHARACTER} transcends")));
+                    if !modifiers::active(Modifier::OneHp) {
+                        let max = pl.hp.max.saturating_sub(TRANSCEND_HP).max(1);
+                        pl.hp.change_max(max);
+                    }
+                    LOG_MSGS.write().unwrap().push(LogMsg::from(format!("{PLAYER_CHARACTER} transcends")));
                     f4_state = 2001001;
                 }
 
                 map.update();
-                display_map(&map, &mut main_wins, &stars_earned);
+                fov::update(&mut map);
+                display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
                 // thread::sleep(delay);
                 let mut did_vfx = false;
                 while map.update_vfx() > 0 {
                     did_vfx = true;
-                    display_map(&map, &mut main_wins, &stars_earned);
+                    display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
                     thread::sleep(delay);
                 }
                 if unsafe { !NEXT_FLOOR } {
-                    display_map(&map, &mut main_wins, &stars_earned);
+                    display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
                     if did_vfx {
                         thread::sleep(vfx_delay);
                     }
@@ -1337,13 +1913,13 @@ fn main() {
 
                         last_floor = time::Instant::now();
 
-                        let mut cur_score: f64 = (50 + (dk * 5 * (FLOORS_CLEARED / 2 + 1))) as f64 * cmb_efficiency;
+                        let mut cur_score: f64 = (50 + (dk * 5 * (DEPTH / 2 + 1))) as f64 * cmb_efficiency;
 
                         if cur_score.is_nan() {
                             cur_score = 0.0;
                         }
                         
-                        score += cur_score;
+                        score += cur_score * modifiers::score_mult();
                     }
 
 
@@ -1353,24 +1929,29 @@ fn main() {
                             break 'main;
                         }
                         FLOORS_CLEARED += 1;
-                        if FLOORS_CLEARED == KILL_SCREEN as u32 - 2 {
-                            score += 500.0;
+                        DEPTH += 1;
+                        if ENDLESS && DEPTH > LAST_NORMAL_FLOOR {
+                            // Keep going back through the normal floors, which get harder each time.
+                            FLOORS_CLEARED = 1 + (DEPTH - 1) % LAST_NORMAL_FLOOR;
+                        } else if FLOORS_CLEARED == KILL_SCREEN as u32 - 2 {
+                            score += 500.0 * modifiers::score_mult();
                             if !*CAN_F4.read().unwrap() {
                                 break 'main;
-                            } else {
+                            } else if !modifiers::active(Modifier::OneHp) {
                                 // Must've transcended, so give max health back.
                                 let pl = map.get_ent_mut(PLAYER).unwrap();
-                                pl.hp.change_max(9);
-                                pl.hp.set_to(9);
+                                let max = pl.hp.max + TRANSCEND_HP;
+                                pl.hp.change_max(max);
+                                pl.hp.set_to(max);
                             }
 
                         } 
                         if FLOORS_CLEARED == KILL_SCREEN as u32 {
-                            score += 1000.0;
+                            score += 1000.0 * modifiers::score_mult();
                             break 'main;
                         }
                         NEXT_FLOOR = false;
-                        gen_floor(
+                        floor_info = gen_floor(
                             &mut map,
                             &mut floor_rng,
                             FLOORS_CLEARED,
@@ -1378,6 +1959,8 @@ fn main() {
                             &templates,
                             &elites,
                         );
+                        camera.set_floor(floor_info.rooms.clone(), PLAYER);
+                        travel.stop();
 
                         let cur_win = &mut floor_end_wins.windows[0];
 
@@ -1386,43 +1969,60 @@ fn main() {
                             style::Color::White,
                             &format!("Time Elapsed: {}:{:02}", real_time / 60, real_time % 60,),
                             cur_win,
-                            floor_win_len as usize,
+                            floor_win_len,
                         );
 
-                        add_line(style::Color::White, &format!("Time Taken: {dt}"), cur_win, floor_win_len as usize);
+                        add_line(style::Color::White, &format!("Time Taken: {dt}"), cur_win, floor_win_len);
 
                         // Enemies killed.
                         add_line(
                             style::Color::White,
                             &format!("Enemies Killed: {}", dk),
                             cur_win,
-                            floor_win_len as usize,
+                            floor_win_len,
                         );
 
                         add_line(
                             style::Color::White,
                             &format!("Combat Efficiency: {:.3}", cmb_efficiency),
                             cur_win,
-                            floor_win_len as usize,
+                            floor_win_len,
                         );
 
                         // Score.
                         let mut score_msg = format!("Score: {score:.2}");
 
-                        if f64::max(score, high_score) == score {
+                        if ENDLESS {
+                            score_msg = format!("{score_msg} (Depth {DEPTH})");
+                        } else if f64::max(score, high_score) == score {
                             score_msg = format!("{score_msg} (New Highscore!)");
                         } else {
                             score_msg = format!("{score_msg} (Best: {high_score:.2})");
                         }
 
-                        add_line(style::Color::White, &score_msg, cur_win, floor_win_len as usize);
+                        add_line(style::Color::White, &score_msg, cur_win, floor_win_len);
+
+                        // Let the player know what the next floor is like.
+                        add_line(
+                            floor_info.theme.clr(),
+                            &format!("Next Floor: {}", floor_info.theme),
+                            cur_win,
+                            floor_win_len,
+                        );
 
                         cur_win.outline_with('#'.grey());
                         floor_end_wins.refresh();
                         let _ = execute!(handle, terminal::Clear(terminal::ClearType::All));
                         print_win(&floor_end_wins);
 
-                        match floor_end_ui.run() {
+                        let draft = upgrades::draft(map.get_ent(PLAYER).unwrap(), &mut draft_rng);
+                        let mut floor_end_ui = make_floor_end_ui(&lay, &basic_button, &draft);
+
+                        match floor_end_ui.run_with(|lay| {
+                            floor_end_wins = recentre_wins(&floor_end_wins, lay, &[(floor_win_len, 12)]);
+                            floor_end_wins.refresh();
+                            print_win(&floor_end_wins);
+                        }) {
                             PLAY => (),
                             END_RUN => {
                                 DEAD = true;
@@ -1431,18 +2031,32 @@ fn main() {
                             QUIT => {
                                 full = true;
                             }
+                            n if n >= UPGRADE && ((n - UPGRADE) as usize) < draft.len() => {
+                                let upgrade = draft[(n - UPGRADE) as usize];
+                                upgrade.apply(map.get_ent_mut(PLAYER).unwrap());
+                                LOG_MSGS.write().unwrap().push(LogEvent::Upgraded(upgrade).into());
+                            }
                             _ => panic!("wtf"),
                         }
 
                         let _ = execute!(handle, terminal::Clear(terminal::ClearType::All));
 
-                        // Restore two health for getting past the boss.
-                        let pl = map.get_ent_mut(PLAYER).unwrap();
-                        let old = *pl.hp.value();
-                        pl.hp += 2;
-                        LOG_MSGS.write().unwrap().push(LogMsg::hp_change(old, &pl.hp, *pl.ch.content()));
+                        // The terminal may have been resized on the floor complete screen.
+                        let new_lay = Layout::wait_for_fit();
+                        if new_lay != lay {
+                            lay = new_lay;
+                            main_wins = make_game_wins(&lay);
+                        }
+
+                        // Restore some health for getting past the boss.
+                        if !modifiers::active(Modifier::NoHealing) {
+                            let pl = map.get_ent_mut(PLAYER).unwrap();
+                            let old = *pl.hp.value();
+                            pl.hp += classes::current().floor_heal();
+                            LOG_MSGS.write().unwrap().push(LogMsg::hp_change(old, &pl.hp, *pl.ch.content()));
+                        }
 
-                        display_map(&map, &mut main_wins, &stars_earned);
+                        display_map(&map, &mut main_wins, &stars_earned, &lay, &mut camera);
                     }
 
                     if brk {
@@ -1471,24 +2085,22 @@ fn main() {
 
         let main_wid = 38;
         let time_taken = time::Instant::now().duration_since(start).as_secs();
-        let (fname, txt_pos) = if unsafe { DEAD } {
-            ("death.txt", Point::new(3, 2))
+        let fname = if unsafe { DEAD } {
+            "death.txt"
+        } else if truely_won || is_puzzle {
+            "win.txt"
         } else {
-            let p = Point::new(26, 2);
-            if truely_won || is_puzzle {
-                ("win.txt", p)
-            } else {
-                ("false_win.txt", p)
-            }
+            "false_win.txt"
         };
-        end_wins.add_win(windowed::Window::new(txt_pos));
-        end_wins.add_win(windowed::Window::new(Point::new(40, 12)));
 
         // Open the relevant file.
         let mut f = fs::File::open(this_path.join(fname)).unwrap();
         let mut text = String::new();
         f.read_to_string(&mut text);
 
+        end_wins.add_win(windowed::Window::new(Point::new(lay.centred(text_wid(&text)), 2)));
+        end_wins.add_win(windowed::Window::new(Point::new(lay.centred(main_wid + 2), 12)));
+
         for line in text.lines() {
             add_line(style::Color::White, line, &mut end_wins.windows[0], 128);
             end_wins.refresh();
@@ -1548,19 +2160,41 @@ fn main() {
 
         add_line(style::Color::White, &turn_msg, cur_win, main_wid);
 
+        // Whatever last hurt the player.
+        if unsafe { DEAD }
+            && let Some(cause) = LOG_MSGS
+                .read()
+                .unwrap()
+                .iter()
+                .rev()
+                .find_map(|msg| msg.event().harm_to(PLAYER_CHARACTER))
+        {
+            add_line(style::Color::White, &format!("Killed By: {cause}"), cur_win, main_wid);
+        }
+
         if !is_puzzle {
             // Floor reached.
+            let reached = if unsafe { ENDLESS } {
+                format!("Depth Reached: {}", unsafe { DEPTH })
+            } else {
+                format!("Floor Reached: {}", unsafe { FLOORS_CLEARED })
+            };
+            add_line(style::Color::White, &reached, cur_win, main_wid);
+
+            // Enemies killed.
             add_line(
                 style::Color::White,
-                &format!("Floor Reached: {}", unsafe { FLOORS_CLEARED },),
+                &format!("Enemies Killed: {}", unsafe { KILLED },),
                 cur_win,
                 main_wid,
             );
 
-            // Enemies killed.
+            // Attacks that hit.
+            let (hits, attacks) = unsafe { (HITS, HITS + MISSES) };
+            let hit_rate = if attacks == 0 { 1.0 } else { hits as f64 / attacks as f64 };
             add_line(
                 style::Color::White,
-                &format!("Enemies Killed: {}", unsafe { KILLED },),
+                &format!("Hits: {hits}/{attacks} ({})", accuracy::percent(hit_rate)),
                 cur_win,
                 main_wid,
             );
@@ -1587,7 +2221,35 @@ fn main() {
             main_wid,
         );
 
-        if !is_puzzle {
+        if unsafe { ENDLESS } {
+            // Endless runs go on the depth leaderboard instead of the highscore.
+            let record = save_file::EndlessRecord {
+                depth: unsafe { DEPTH },
+                score,
+                seed: unsafe { SEED },
+                class: classes::current(),
+            };
+            let mut score_msg = format!("Score: {score:.2}");
+            match save_file::add_endless_record(&mut endless_records, record) {
+                Some(0) => score_msg = format!("{score_msg} (Deepest Run!)"),
+                Some(rank) => score_msg = format!("{score_msg} (Rank {})", rank + 1),
+                None => {
+                    let best = endless_records.first().map(|r| r.depth).unwrap_or(0);
+                    score_msg = format!("{score_msg} (Best Depth: {best})");
+                }
+            }
+
+            // Score.
+            add_line(style::Color::White, &score_msg, cur_win, main_wid);
+
+            // Seed used.
+            add_line(
+                style::Color::White,
+                &run_code_line(),
+                cur_win,
+                main_wid,
+            );
+        } else if !is_puzzle {
             high_score = f64::max(score, high_score);
             let mut score_msg = format!("Score: {score:.2}");
             if score == high_score {
@@ -1599,10 +2261,21 @@ fn main() {
             // Score.
             add_line(style::Color::White, &score_msg, cur_win, main_wid);
 
+            // Best score as this class.
+            let class = classes::current();
+            let class_best = class_scores.entry(class).or_insert(0.0);
+            *class_best = f64::max(score, *class_best);
+            add_line(
+                style::Color::White,
+                &format!("Class: {class} (Best: {class_best:.2})"),
+                cur_win,
+                main_wid,
+            );
+
             // Seed used.
             add_line(
                 style::Color::White,
-                &format!("Seed: {:X}", unsafe { SEED },),
+                &run_code_line(),
                 cur_win,
                 main_wid,
             );
@@ -1622,7 +2295,21 @@ fn main() {
         } else {
             2
         });
-        match menu_container.run() {
+        let end_places = [(text_wid(&text), 2), (main_wid + 2, 12)];
+        let mut redraw_end = |lay: &Layout| {
+            end_wins = recentre_wins(&end_wins, lay, &end_places);
+            end_wins.refresh();
+            print_win(&end_wins);
+        };
+        let mut code = menu_container.run_with(&mut redraw_end);
+        while code == VIEW_LOG {
+            log_view::view_log(&log_header());
+            redraw_end(&Layout::wait_for_fit());
+            menu_container.reprint();
+            code = menu_container.run_with(&mut redraw_end);
+        }
+
+        match code {
             QUIT => break 'full,
             MAIN_MENU => (),
             QUICK_RESET => quick_restart = true,
@@ -1649,22 +2336,290 @@ fn main() {
     // Write high_score to file.
     save_file::save_highscore(high_score);
 
+    // Write the endless leaderboard to file.
+    save_file::save_endless(&endless_records);
+    save_file::save_class_scores(&class_scores);
+
     // Write kill counts to file.
     save_file::save_kills(&*KILL_COUNTS.read().unwrap());
 
     // Write whether we have won yet to the file.
     save_file::save_won(won_yet);
 
+    // Write the settings so that they can be found and edited.
+    settings::save_settings(&settings::SETTINGS);
+
     // Put the terminal in a "normal" state in case the player actually wants to use it afterwards.
     terminal::disable_raw_mode();
     execute!(
         io::stdout(),
+        event::DisableMouseCapture,
         terminal::Clear(terminal::ClearType::All),
         cursor::MoveTo(0, 0),
         cursor::Show,
     );
 }
 
+/// Create the windows used during the game in the places given by the layout.
+fn make_game_wins(lay: &Layout) -> windowed::Container<style::StyledContent<char>> {
+    let mut main_wins = windowed::Container::new();
+
+    main_wins.add_win(windowed::Window::new(lay.game));
+    main_wins.add_win(windowed::Window::new(lay.stats));
+    main_wins.add_win(windowed::Window::new(lay.atks));
+    main_wins.add_win(windowed::Window::new(lay.keys));
+    main_wins.add_win(windowed::Window::new(lay.log));
+    main_wins.add_win(windowed::Window::new(lay.debug));
+    main_wins.add_win(windowed::Window::new(lay.seed));
+    main_wins.add_win(windowed::Window::new(lay.puzzle));
+    main_wins.add_win(windowed::Window::new(lay.items));
+
+    main_wins
+}
+
+/// Create the screen for choosing which class to play as. The class currently chosen is shown in
+/// green, and choosing one exits with CHOOSE_CLASS plus its index.
+fn make_class_scene(lay: &Layout, basic_button: &ui::widgets::Button) -> ui::Scene {
+    let class_wid = 46;
+    let mut class_scene = ui::Scene::new(
+        Point::new(lay.centred(class_wid), 19),
+        class_wid,
+        Class::ALL.len() + 4,
+    );
+    let cur = *CLASS.read().unwrap();
+
+    for (n, c) in Class::ALL.into_iter().enumerate() {
+        let pos = Point::new(1, n as i32 + 1);
+        let hp = c.template().max_hp;
+        class_scene.add_element(
+            Box::new(
+                basic_button
+                    .clone()
+                    .set_txt(format!("{c}: {hp} hp, {}", c.desc()))
+                    .set_clr(if c == cur { style::Color::Green } else { style::Color::White })
+                    .set_event(ui::Event::Exit(CHOOSE_CLASS + n as u32))
+                    .set_screen_pos(pos),
+            ),
+            pos,
+        );
+    }
+    class_scene.add_element(
+        Box::new(
+            basic_button
+                .clone()
+                .set_txt(String::from("Back"))
+                .set_event(ui::Event::ChangeScene(0))
+                .set_screen_pos(Point::new(1, Class::ALL.len() as i32 + 2)),
+        ),
+        Point::new(1, Class::ALL.len() as i32 + 1),
+    );
+    class_scene.add_element(
+        Box::new(ui::widgets::Outline::new('#'.grey(), class_wid)),
+        Point::new(999, 999),
+    );
+
+    class_scene.move_cursor(Point::new(1, 1));
+    class_scene
+}
+
+/// Create the screen listing the endless mode leaderboard, with the depth, score, seed and class
+/// of each run.
+fn make_records_scene(
+    lay: &Layout,
+    basic_button: &ui::widgets::Button,
+    records: &[save_file::EndlessRecord],
+) -> ui::Scene {
+    let records_wid = 58;
+    let lines = records.len().max(1);
+    let mut records_scene = ui::Scene::new(
+        Point::new(lay.centred(records_wid), 17),
+        records_wid,
+        lines + 4,
+    );
+
+    let mut texts: Vec<String> = records
+        .iter()
+        .enumerate()
+        .map(|(n, r)| format!("{:>2}. Depth {}, {:.2}, {:X}, {}", n + 1, r.depth, r.score, r.seed, r.class))
+        .collect();
+    if texts.is_empty() {
+        texts.push(String::from("No endless runs yet"));
+    }
+    for (n, txt) in texts.into_iter().enumerate() {
+        records_scene.add_element(
+            Box::new(ui::widgets::Label::new(txt, Point::new(1, n as i32 + 1))),
+            Point::new(0, n as i32 + 1),
+        );
+    }
+    records_scene.add_element(
+        Box::new(
+            basic_button
+                .clone()
+                .set_txt(String::from("Back"))
+                .set_event(ui::Event::ChangeScene(0))
+                .set_screen_pos(Point::new(1, lines as i32 + 2)),
+        ),
+        Point::new(1, 1),
+    );
+    records_scene.add_element(
+        Box::new(ui::widgets::Outline::new('#'.grey(), records_wid)),
+        Point::new(999, 999),
+    );
+
+    records_scene.move_cursor(Point::new(1, 1));
+    records_scene
+}
+
+/// Create the menu shown when a floor is completed. Each upgrade in the draft exits with
+/// UPGRADE plus its index, and continues on to the next floor.
+fn make_floor_end_ui(lay: &Layout, basic_button: &ui::widgets::Button, draft: &[upgrades::Upgrade]) -> ui::UiContainer {
+    let mut floor_end_ui = ui::UiContainer::new();
+
+    // Room for the upgrades, a label above them and a gap below them.
+    let draft_hgt = if draft.is_empty() { 0 } else { draft.len() + 2 };
+    let wid = if draft.is_empty() { 12 } else { 20 };
+    let mut scene = ui::Scene::new(Point::new(lay.centred(wid), 20), wid, 5 + draft_hgt);
+
+    // Open the floor complete file.
+    let mut f = fs::File::open(get_assets_path().join("floor_complete.txt")).unwrap();
+    let mut main_text = String::new();
+    f.read_to_string(&mut main_text);
+
+    let title = ui::widgets::Title::new(Point::new(lay.centred(text_wid(&main_text)), 2), main_text, None);
+    scene.add_element(Box::new(title), Point::new(500, 500));
+
+    if draft.is_empty() {
+        scene.add_element(
+            Box::new(
+                basic_button
+                    .clone()
+                    .set_txt(String::from("Continue"))
+                    .set_events(vec![
+                        ui::Event::Broadcast(String::from("clr")),
+                        ui::Event::Exit(PLAY)
+                    ])
+                    .set_screen_pos(Point::new(1, 1)),
+            ),
+            Point::new(1, 1),
+        );
+    } else {
+        scene.add_element(
+            Box::new(ui::widgets::Label::new(
+                String::from("Choose an upgrade:"),
+                Point::new(1, 1),
+            )),
+            Point::new(0, 1),
+        );
+        for (n, upgrade) in draft.iter().enumerate() {
+            scene.add_element(
+                Box::new(
+                    basic_button
+                        .clone()
+                        .set_txt(upgrade.to_string())
+                        .set_clr(upgrade.clr())
+                        .set_events(vec![
+                            ui::Event::Broadcast(String::from("clr")),
+                            ui::Event::Exit(UPGRADE + n as u32)
+                        ])
+                        .set_screen_pos(Point::new(1, n as i32 + 2)),
+                ),
+                Point::new(1, n as i32 + 1),
+            );
+        }
+    }
+
+    // Skip past the draft, which takes the place of the continue button.
+    let below = draft.len().max(1) as i32;
+    let screen_below = if draft.is_empty() { 1 } else { draft.len() as i32 + 2 };
+    scene.add_element(
+        Box::new(
+            basic_button
+                .clone()
+                .set_txt(String::from("End Run"))
+                .set_event(ui::Event::Exit(END_RUN))
+                .set_screen_pos(Point::new(1, screen_below + 1)),
+        ),
+        Point::new(1, below + 1),
+    );
+    scene.add_element(
+        Box::new(
+            basic_button
+                .clone()
+                .set_txt(String::from("Quit"))
+                .set_event(ui::Event::Exit(QUIT))
+                .set_screen_pos(Point::new(1, screen_below + 2)),
+        ),
+        Point::new(1, below + 2),
+    );
+    scene.add_element(
+        Box::new(ui::widgets::Outline::new('#'.grey(), wid)),
+        Point::new(999, 999),
+    );
+    scene.move_cursor(Point::new(1, 1));
+
+    floor_end_ui.add_scene(scene);
+    floor_end_ui
+}
+
+/// Returns the text shown at the top of the log viewer and its exported file.
+fn log_header() -> String {
+    unsafe {
+        match PUZZLE {
+            Some(idx) => format!("Puzzle {}", idx + 1),
+            None => format!(
+                "Seed {}, {}",
+                MODIFIERS.read().unwrap().run_code(SEED),
+                classes::current()
+            ),
+        }
+    }
+}
+
+/// Returns the tags of the active modifiers, for the seed window.
+fn mods_txt() -> String {
+    let mods = MODIFIERS.read().unwrap();
+    if mods.is_empty() {
+        String::from("none")
+    } else {
+        mods.tags()
+    }
+}
+
+/// Returns the line of the end screen that tells the player how to play the run again.
+fn run_code_line() -> String {
+    let mods = MODIFIERS.read().unwrap();
+    let seed = unsafe { SEED };
+    if mods.is_empty() {
+        format!("Seed: {seed:X}")
+    } else {
+        format!("Run Code: {}", mods.run_code(seed))
+    }
+}
+
+/// Returns the number of characters in the longest line of the text.
+fn text_wid(txt: &str) -> usize {
+    txt.lines().map(|ln| ln.chars().count()).max().unwrap_or(0)
+}
+
+/// Returns the windows moved across to the middle of the terminal, such as after it is resized.
+/// Each window is given with the width it is centred by and the row it starts on. Like
+/// [ui::Scene] does after a resize, this builds a new container, so the next print draws every
+/// window again.
+fn recentre_wins(
+    wins: &windowed::Container<style::StyledContent<char>>,
+    lay: &Layout,
+    places: &[(usize, i32)],
+) -> windowed::Container<style::StyledContent<char>> {
+    let mut moved = windowed::Container::new();
+    for (win, &(wid, y)) in wins.windows.iter().zip(places) {
+        let mut new_win = windowed::Window::new(Point::new(lay.centred(wid), y));
+        new_win.data = win.data.clone();
+        moved.add_win(new_win);
+    }
+
+    moved
+}
+
 /// Clears all events currently in the queue.
 fn clear_events() {
     while let Ok(b) = event::poll(time::Duration::from_secs(0))
@@ -1674,6 +2629,25 @@ fn clear_events() {
     }
 }
 
+/// Returns the direction a movement key points in, using the same keys as moving the player.
+fn dir_from_key(code: event::KeyCode) -> Option<Point> {
+    match code {
+        event::KeyCode::Left | event::KeyCode::Char('a') | event::KeyCode::Char('h') => {
+            Some(Point::new(-1, 0))
+        }
+        event::KeyCode::Right | event::KeyCode::Char('d') | event::KeyCode::Char('l') => {
+            Some(Point::new(1, 0))
+        }
+        event::KeyCode::Down | event::KeyCode::Char('s') | event::KeyCode::Char('j') => {
+            Some(Point::new(0, -1))
+        }
+        event::KeyCode::Up | event::KeyCode::Char('w') | event::KeyCode::Char('k') => {
+            Some(Point::new(0, 1))
+        }
+        _ => None,
+    }
+}
+
 /// Colours the text with the given colour and puts it into the window. Ensures at least len styled characters
 /// are contained within the line.
 fn add_line(clr: style::Color,
@@ -1695,76 +2669,6 @@ fn add_line(clr: style::Color,
     win.data.push(line);
 }
 
-/// Checks some seeds for suspicousness. Returns true if any are sus.
-#[cfg(debug_assertions)]
-fn check_seeds(init_seed: u64, sds: u64) -> bool {
-    let meta = templates::metadata::get_metadata();
-    let (templates, elites) = templates::get_templates();
-    let mut map = bn::Map::new(69, 69);
-
-    let mut found_fault = false;
-
-    for sd in init_seed..init_seed + sds {
-        let mut floor_rng = rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(sd);
-        eprint!("Trying {sd:X}");
-        eprint!("\r");
-        gen_floor(&mut map, &mut floor_rng, 0, &meta, &templates, &elites);
-        let test = |t: Option<&Tile>| {
-            if let Some(t) = t
-                && t.door
-            {
-                true
-            } else {
-                false
-            }
-        };
-        let depth = 25;
-        for y in -depth..=depth {
-            for x in -depth..=depth {
-                let pr = Point::new(x, y);
-                let hell_ps = [Point::new(x - 1, y), Point::new(x, y - 1)];
-                if test(map.get_map(pr)) {
-                    let mut wall_count = 0;
-                    for dis in Point::ORIGIN.get_all_adjacent() {
-                        match map.get_map(pr + dis) {
-                            Some(t) => {
-                                if t.blocking {
-                                    wall_count += 1
-                                }
-                            }
-                            None => continue,
-                        }
-                    }
-
-                    match wall_count {
-                        0 | 1 => {
-                            found_fault = true;
-                            eprintln!("{sd:X} at {pr} sus door");
-                        }
-                        3 | 4 => {
-                            eprintln!("{sd:X} at {pr} impassable door");
-                            found_fault = true;
-                        }
-                        _ => (),
-                    }
-
-                    if hell_ps.into_iter().all(|p| test(map.get_map(p))) {
-                        found_fault = true;
-                        eprintln!("{sd:X} at {pr} door hell");
-                    }
-                }
-            }
-        }
-    }
-
-    if !found_fault {
-        // Clear the line if everything is a-ok
-        eprintln!("\r")
-    }
-
-    found_fault
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1774,9 +2678,10 @@ mod tests {
         let sds = 1024;
 
         let init_seed = rand::rng().random_range(0..u64::MAX - sds);
-        let found_fault = check_seeds(init_seed, sds);
-        if found_fault {
-            panic!();
+        let reports = map_gen::validate::check_seeds(init_seed, sds);
+        for report in &reports {
+            eprintln!("{report}");
         }
+        assert!(reports.is_empty());
     }
 }
@@ -7,6 +7,11 @@ use std::collections::{HashMap, VecDeque};
 
 pub mod bandit_gen;
 
+pub mod shapes;
+use shapes::Shape;
+
+pub mod validate;
+
 /// A singular cell in a map.
 #[derive(Debug)]
 pub enum Cell {
@@ -407,14 +412,16 @@ pub fn map_gen<R: Rng>(
     (occupied, rects)
 }
 
-/// Turns rooms at random into ice rooms. Cuts the corners of the other rooms.
+/// Turns rooms at random into ice rooms, and gives the others shapes picked from the weighted mix.
 pub fn add_ice<R: Rng>(
     rects: &mut [Rect],
     occupied: &mut HashMap<Point, Cell>,
     rng: &mut R,
     ice_prevalence: f64,
+    shapes: &[(Shape, u32)],
     illegal_hosts: &[usize],
 ) {
+    let mut shaped = Vec::new();
     for id in 0..rects.len() {
         if illegal_hosts.contains(&id) {
             continue;
@@ -422,6 +429,17 @@ pub fn add_ice<R: Rng>(
         if rng.random_bool(ice_prevalence) {
             ice_rect(rects, occupied, rng, id, 0.25, 0);
         } else {
+            match shapes.choose_weighted(rng, |(_, w)| *w).map_or(Shape::Plain, |(s, _)| *s) {
+                Shape::Plain => cut_corners(&rects[id], occupied, rng),
+                shape => shaped.push((id, shape)),
+            }
+        }
+    }
+
+    // Shape rooms last, so cutting the corners of their neighbours can't eat into them. Rooms
+    // the shape doesn't fit in are left plain instead.
+    for (id, shape) in shaped {
+        if !shape.apply(&rects[id], id, occupied, rng) {
             cut_corners(&rects[id], occupied, rng);
         }
     }
@@ -3,8 +3,11 @@
 use super::*;
 use crate::*;
 use entity::*;
+use items::Item;
+use modifiers::Modifier;
 use std::collections::HashMap;
 use templates::metadata::TempMeta;
+use themes::{Features, Theme};
 use tile_presets::*;
 use rand::seq::IteratorRandom;
 
@@ -20,10 +23,50 @@ pub const MIN_WIDTH: i32 = 6;
 pub const F4_RM_SIZE: i32 = 15;
 /// Number of rooms on floor 4.
 pub const F4_ROOMS: usize = 25;
+/// Last floor generated normally in endless mode. After it, floors go back to floor 1 but get harder.
+pub const LAST_NORMAL_FLOOR: u32 = 3;
+/// Percentage increase to the enemy budget of each room for every floor past the last normal floor.
+pub const ENDLESS_BUDGET_SCALE: u32 = 25;
+/// Floors past the last normal floor it takes for enemies to gain an extra hp.
+pub const ENDLESS_HP_FLOORS: u32 = 2;
+/// Most hp endless mode will raise an enemy to, as health is shown as a single hex digit.
+pub const ENDLESS_MAX_HP: u32 = 15;
+
+/// Chance of the item on a floor being locked in a vault opened by a pressure plate.
+pub const VAULT_CHANCE: f64 = 0.4;
+/// Chance of a vault's plate only holding the gate open while something stands on it, rather than
+/// opening it for good.
+pub const HOLD_VAULT_CHANCE: f64 = 0.5;
 
 /// True if the map should be generated with bonus ice puzzle rooms.
 pub const EXTRA_ICE: bool = if cfg!(debug_assertions) { false } else { false };
 
+/// Returns how many floors past the last normal floor the player is in endless mode, or 0 if
+/// they aren't.
+pub fn endless_extra() -> u32 {
+    unsafe {
+        if ENDLESS {
+            DEPTH.saturating_sub(LAST_NORMAL_FLOOR)
+        } else {
+            0
+        }
+    }
+}
+
+/// Returns every floor, in order, that at least one of the templates can be found on, not
+/// counting the final floor.
+fn pool_floors(templates: &[EntityTemplate], meta: &HashMap<char, TempMeta>) -> Vec<u32> {
+    let mut floors: Vec<u32> = templates
+        .iter()
+        .flat_map(|t| meta[t.ch.content()].floor_rang.clone())
+        .filter(|f| *f < KILL_SCREEN as u32 - 1)
+        .collect();
+    floors.sort();
+    floors.dedup();
+
+    floors
+}
+
 fn get_temp<'a, R: rand::Rng>(
     budget: u32,
     rng: &mut R,
@@ -31,6 +74,7 @@ fn get_temp<'a, R: rand::Rng>(
     meta: &HashMap<char, TempMeta>,
     templates: &'a [EntityTemplate],
     floor_num: u32,
+    theme: Theme,
 ) -> Option<(&'a EntityTemplate, u32)> {
     let possible: Vec<_> = templates
         .iter()
@@ -55,7 +99,24 @@ fn get_temp<'a, R: rand::Rng>(
             }
         })
         .collect();
-    possible.choose(rng).cloned()
+    possible
+        .choose_weighted(rng, |(t, _)| theme.enemy_weight(*t.ch.content()))
+        .ok()
+        .cloned()
+}
+
+/// Information about a generated floor that is not easy to get back out of the map.
+#[derive(Clone, Debug, Default)]
+pub struct FloorInfo {
+    /// Every room on the floor, including its walls. The player starts in the first one.
+    pub rooms: Vec<Rect>,
+    /// Position of the exit, if there is one.
+    pub exit: Option<Point>,
+    /// Positions of the keys, with the id of each. Keys open locks whose id matches theirs,
+    /// modulo the number of key colours.
+    pub keys: Vec<(Point, u32)>,
+    /// Theme of the floor.
+    pub theme: Theme,
 }
 
 /// Generate a single floor of an untitled_bandit game.
@@ -66,14 +127,27 @@ pub fn gen_floor<R: rand::Rng>(
     meta: &HashMap<char, TempMeta>,
     templates: &[EntityTemplate],
     elites: &[EntityTemplate],
-) {
+) -> FloorInfo {
     // Display a message saying that we entered the floor.
-    let flr_text = if floor_num < 5 { floor_num.to_string() } else { String::from("???") };
-    LOG_MSGS.write().unwrap().push(LogMsg::new(format!("{} enters floor {flr_text}", templates::PLAYER_CHARACTER)));
+    let flr_text = if unsafe { ENDLESS } {
+        unsafe { DEPTH.to_string() }
+    } else if floor_num < 5 {
+        floor_num.to_string()
+    } else {
+        String::from("???")
+    };
+    let extra = endless_extra();
+    let theme = Theme::pick(rng, floor_num);
+    unsafe { themes::THEME = theme };
+    LOG_MSGS.write().unwrap().push(LogMsg::new(LogEvent::FloorEntered(flr_text)));
 
     // Create the player if it is the first floor, otherwise get them.
     let pl = if floor_num == 0 {
-        templates::get_player()
+        let mut pl = classes::get_player();
+        if modifiers::active(Modifier::OneHp) {
+            pl.hp.change_max(1);
+        }
+        pl
     } else {
         map.get_ent(unsafe { PLAYER }).unwrap().clone()
     };
@@ -87,7 +161,14 @@ pub fn gen_floor<R: rand::Rng>(
     }
     map.insert_entity(pl, unsafe { PLAYER });
 
-    let ice_prevalence = if EXTRA_ICE { 1.0 } else { 0.15 };
+    let no_ice = modifiers::active(Modifier::NoIce);
+    let ice_prevalence = if no_ice {
+        0.0
+    } else if EXTRA_ICE {
+        1.0
+    } else {
+        theme.ice()
+    };
     let rooms = if floor_num >= 4 { 1 } else { ROOMS - SPECIAL_ROOMS + floor_num * 3 };
 
     // Generate the rooms of the map.
@@ -100,6 +181,7 @@ pub fn gen_floor<R: rand::Rng>(
 
     // Ids of all key rooms so we know not to put enemies in them.
     let mut key_ids = Vec::new();
+    let mut keys = Vec::new();
     let mut true_door = None;
     // Default value so it doesn't appear on floor 5.
     let mut exit_pos: Point = Point::new(100, 100);
@@ -123,7 +205,7 @@ pub fn gen_floor<R: rand::Rng>(
             if key_tot == 1 {
                 i = floor_num;
             }
-            gen_key_room(rng, map, &mut rooms, &mut grid, i, &ill_hosts);
+            keys.push((gen_key_room(rng, map, &mut rooms, &mut grid, i, &ill_hosts), i));
         }
 
         // Create some ice puzzles.
@@ -132,6 +214,7 @@ pub fn gen_floor<R: rand::Rng>(
             &mut grid,
             rng,
             ice_prevalence,
+            theme.shapes(),
             // Do not touch the key room(s).
             &ill_hosts
         );
@@ -210,10 +293,11 @@ pub fn gen_floor<R: rand::Rng>(
                     let rm = &rooms[n];
                     let rm_centre = rm.top_left() + Point::new(rm.wid / 2, rm.hgt / -2);
                     map.insert_tile(get_key(false, times_keyed), rm_centre);
+                    keys.push((rm_centre, times_keyed));
                     times_keyed += 1;
                     key_ids.push(n);
                 }
-            } else if rng.random_bool(0.15) {
+            } else if !no_ice && rng.random_bool(0.15) {
                 map_gen::ice_rect(&mut rooms, &mut grid, rng, n, 0.1, 5);
             }
         }
@@ -228,6 +312,7 @@ pub fn gen_floor<R: rand::Rng>(
             revealed: false,
             door: false,
             locked: None,
+            mechanism: None,
             slippery: false,
             step_effect: None,
         };
@@ -278,14 +363,19 @@ pub fn gen_floor<R: rand::Rng>(
         }
 
         let mut budget = area as u32 + floor_num * 30;
+        if modifiers::active(Modifier::DoubleBudget) {
+            budget *= 2;
+        }
 
         // Create a boss in the exit room.
         let elite = n == exit_id;
         let mut over_ride = floor_num == 5;
 
-        let f_num: u32;
+        let mut f_num: u32;
 
-        if floor_num == 4 && rng.random_bool(0.3) && !elite {
+        let elite_floor = floor_num == 4
+            || (floor_num < 4 && modifiers::active(Modifier::EliteFloors));
+        if elite_floor && rng.random_bool(0.3) && !elite {
             over_ride = true;
             f_num = rng.random_range(1..=3);
         } else {
@@ -299,7 +389,60 @@ pub fn gen_floor<R: rand::Rng>(
             templates 
         };
 
-        populate(rng, budget, map, templates, meta, &cells, f_num);
+        // Past the normal floors, go through the enemies of every floor and make them tougher.
+        if extra > 0 {
+            let floors = pool_floors(templates, meta);
+            f_num = floors[extra as usize % floors.len()];
+            budget += budget * extra * ENDLESS_BUDGET_SCALE / 100;
+        }
+
+        populate(rng, budget, map, templates, meta, &cells, f_num, theme);
+
+        // Mark everything just put in the room if it came from the elites, and give them their
+        // shields.
+        if elite || over_ride {
+            for p in &cells {
+                if let Some(e) = map.get_ent_mut(*p) {
+                    e.elite = true;
+                    e.add_status(status::Status::new(status::StatusKind::Shield, status::ELITE_SHIELD));
+                }
+            }
+        }
+    }
+
+    // Leave an item lying around somewhere other than the start, key and exit rooms, sometimes
+    // locked away in a vault.
+    if floor_num < 5
+        && let Some(r) = (1..rooms.len())
+            .filter(|n| *n != exit_id && !key_ids.contains(n))
+            .choose(rng)
+            .map(|n| rooms[n])
+    {
+        let item = get_item(false, Item::ALL[rng.random_range(0..Item::ALL.len())]);
+        let hold = rng.random_bool(HOLD_VAULT_CHANCE);
+        if rng.random_bool(VAULT_CHANCE)
+            && let Some(pos) = build_vault(rng, map, &grid, &r, hold)
+        {
+            map.insert_tile(item, pos);
+        } else if let Some(pos) = r
+            .inner_cells()
+            .filter(|p| {
+                matches!(grid.get(p), Some(Cell::Inner(_)))
+                    && map.get_ent(*p).is_none()
+                    && map.get_map(*p).is_none()
+                    && *p != exit_pos
+            })
+            .choose(rng)
+        {
+            map.insert_tile(item, pos);
+        }
+    }
+
+    // Scatter traps and other features through the ordinary rooms.
+    if floor_num < 5 {
+        for n in (1..rooms.len()).filter(|n| *n != exit_id && !key_ids.contains(n)) {
+            add_features(rng, map, &grid, &rooms[n], theme.features());
+        }
     }
 
     // Place the exit tile.
@@ -350,6 +493,7 @@ pub fn gen_floor<R: rand::Rng>(
             door,
             step_effect: None,
             locked: None,
+            mechanism: None,
         };
 
         map.insert_tile(t, pos);
@@ -357,10 +501,192 @@ pub fn gen_floor<R: rand::Rng>(
 
     if let Some(true_door) = true_door {
         let door = map.get_map_mut(true_door).unwrap();
-        door.ch = Some(LOCKED_DOOR.with(KEY_CLRS[floor_num as usize % 4]));
+        door.ch = Some(glyphs().locked_door.with(KEY_CLRS[floor_num as usize % 4]));
         door.locked = Some(floor_num);
         door.blocking = true;
     }
+
+    // Look around the starting room.
+    fov::update(map);
+
+    FloorInfo {
+        rooms,
+        exit: if floor_num < 5 { Some(exit_pos) } else { None },
+        keys,
+        theme,
+    }
+}
+
+/// Generate the floors of the run from the seed in order, as the game does, up to and including
+/// the last floor. The function is given each floor as soon as it has been generated.
+pub fn gen_run<F: FnMut(u32, &bandit::Map<En>, &FloorInfo)>(
+    seed: u64,
+    last_floor: u32,
+    meta: &HashMap<char, TempMeta>,
+    templates: &[EntityTemplate],
+    elites: &[EntityTemplate],
+    mut each: F,
+) {
+    let mut floor_rng = rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(seed);
+    let mut map = bandit::Map::new(69, 69);
+
+    for floor in 0..=last_floor {
+        unsafe {
+            FLOORS_CLEARED = floor;
+            DEPTH = floor;
+        }
+        let info = gen_floor(&mut map, &mut floor_rng, floor, meta, templates, elites);
+        each(floor, &map, &info);
+    }
+}
+
+/// Returns the cells of the room that features can go in, which are floor tiles away from the
+/// doors with nothing else in them.
+fn free_cells(map: &bandit::Map<En>, grid: &HashMap<Point, Cell>, rm: &Rect) -> Vec<Point> {
+    rm.inner_cells()
+        .filter(|p| {
+            matches!(grid.get(p), Some(Cell::Inner(_)))
+                && map.get_ent(*p).is_none()
+                && map.get_map(*p).is_none()
+                && Rect::new(-2, 2, 5, 5)
+                    .cells()
+                    .all(|d| !grid.get(&(d + *p)).is_some_and(|cl| cl.is_door()))
+        })
+        .collect()
+}
+
+/// Walls off a cell of the room with cracked walls and a gate, with a pressure plate elsewhere in
+/// the room that opens it. Returns the cell inside, or None if there was no room for it or no way
+/// to open it.
+///
+/// If hold is false, the plate opens the gate for good. Otherwise the gate is only open while
+/// something stands on the plate, so an enemy has to be lured onto it before the room is cleared.
+/// Hold vaults are a cell deeper, with a plate just inside the gate so nobody is ever shut in.
+fn build_vault<R: Rng>(
+    rng: &mut R,
+    map: &mut bandit::Map<En>,
+    grid: &HashMap<Point, Cell>,
+    rm: &Rect,
+    hold: bool,
+) -> Option<Point> {
+    let cells = free_cells(map, grid, rm);
+    let dir = Point::ORIGIN.get_all_adjacent()[rng.random_range(0..4)];
+    let depth = if hold { 2 } else { 1 };
+
+    // Keep the walls away from the edges of the room so it can't be cut in two.
+    let centre = *cells
+        .iter()
+        .filter(|p| {
+            (0..depth).map(|n| **p + dir * n).all(|inside| {
+                Rect::new(-2, 2, 5, 5)
+                    .cells()
+                    .all(|d| matches!(grid.get(&(d + inside)), Some(Cell::Inner(_))))
+                    && inside
+                        .get_all_adjacent_diagonal()
+                        .into_iter()
+                        .all(|q| cells.contains(&q))
+            })
+        })
+        .choose(rng)?;
+    let inside: Vec<Point> = (0..depth).map(|n| centre + dir * n).collect();
+    let gate = centre + dir * depth;
+    // The gate has to be walked up to from inside the room.
+    let outside = gate + dir;
+    if !cells.contains(&outside) {
+        return None;
+    }
+    let plate = *cells
+        .iter()
+        .filter(|p| inside.iter().all(|q| p.dist_squared(*q) > 4))
+        .choose(rng)?;
+
+    for q in &inside {
+        for p in q.get_all_adjacent_diagonal() {
+            if p != gate && !inside.contains(&p) {
+                map.insert_tile(get_cracked_wall(false), p);
+            }
+        }
+    }
+    map.insert_tile(get_gate(false, Some(plate)), gate);
+    if hold {
+        map.insert_tile(get_plate(false, Some(gate)), plate);
+        // Stepping back onto this on the way out opens the gate again.
+        map.insert_tile(get_plate(false, Some(gate)), gate - dir);
+    } else {
+        map.insert_tile(get_latch_plate(false, Some(gate)), plate);
+    }
+    // Keep features from being put in front of the gate.
+    map.insert_tile(Tile { empt: false, ..Tile::default() }, outside);
+
+    Some(centre)
+}
+
+/// Maybe adds some spike traps, a pair of teleporters, a conveyor belt or a cracked wall to the
+/// room, with the given chances.
+fn add_features<R: Rng>(
+    rng: &mut R,
+    map: &mut bandit::Map<En>,
+    grid: &HashMap<Point, Cell>,
+    rm: &Rect,
+    features: Features,
+) {
+    let mut cells = free_cells(map, grid, rm);
+    cells.shuffle(rng);
+
+    // Cracked walls go where they are surrounded by floor so they never block the way.
+    if rng.random_bool(features.cracked)
+        && let Some(&start) = cells.iter().find(|p| {
+            p.get_all_adjacent_diagonal()
+                .into_iter()
+                .all(|q| matches!(grid.get(&q), Some(Cell::Inner(_))))
+        })
+    {
+        let dir = Point::ORIGIN.get_all_adjacent()[rng.random_range(0..4)];
+        let mut p = start;
+        for _ in 0..rng.random_range(1..=3) {
+            if !cells.contains(&p)
+                || !p.get_all_adjacent_diagonal()
+                    .into_iter()
+                    .all(|q| matches!(grid.get(&q), Some(Cell::Inner(_))))
+            {
+                break;
+            }
+            map.insert_tile(get_cracked_wall(false), p);
+            cells.retain(|c| *c != p);
+            p = p + dir;
+        }
+    }
+
+    if rng.random_bool(features.spikes) {
+        for _ in 0..rng.random_range(1..=3) {
+            if let Some(p) = cells.pop() {
+                map.insert_tile(get_spikes(false), p);
+            }
+        }
+    }
+
+    if rng.random_bool(features.teleporters) && cells.len() >= 2 {
+        let a = cells.pop().unwrap();
+        let b = cells.pop().unwrap();
+        map.insert_tile(get_teleporter(false, Some(b)), a);
+        map.insert_tile(get_teleporter(false, Some(a)), b);
+    }
+
+    // Conveyors only ever push onto free floor, so nothing gets thrown into a wall.
+    if rng.random_bool(features.conveyors)
+        && let Some(&start) = cells.last()
+    {
+        let dir = Point::ORIGIN.get_all_adjacent()[rng.random_range(0..4)];
+        let mut p = start;
+        for _ in 0..rng.random_range(2..=4) {
+            if !cells.contains(&p) || !cells.contains(&(p + dir)) {
+                break;
+            }
+            map.insert_tile(create_conveyor(dir, false), p);
+            cells.retain(|c| *c != p);
+            p = p + dir;
+        }
+    }
 }
 
 /// Puts some enemies into the room.
@@ -372,15 +698,17 @@ fn populate<R: Rng>(
     meta: &HashMap<char, TempMeta>,
     valid: &[Point],
     floor_num: u32,
+    theme: Theme,
 ) {
     let mut budget = budget;
+    let extra_hp = endless_extra() / ENDLESS_HP_FLOORS;
     let mut cells = Vec::from(valid);
     cells.shuffle(rng);
 
     let mut temp_counts = HashMap::new();
 
     'enemy_gen: while let Some((temp, cost)) =
-        get_temp(budget, rng, &temp_counts, meta, templates, floor_num)
+        get_temp(budget, rng, &temp_counts, meta, templates, floor_num, theme)
     {
         budget -= cost;
         // Exit early if there is no where to place the entity.
@@ -390,6 +718,10 @@ fn populate<R: Rng>(
 
         *temp_counts.entry(*temp.ch.content()).or_insert(0) += 1;
         let mut en = En::from_template(temp, false, true);
+        if extra_hp > 0 && en.hp.max < ENDLESS_MAX_HP {
+            let max = (en.hp.max + extra_hp).min(ENDLESS_MAX_HP);
+            en.hp.change_max(max);
+        }
         if floor_num == 5 {
             en.dormant = false;
             en.special = Special::FinalBoss;
@@ -401,7 +733,7 @@ fn populate<R: Rng>(
     }
 }
 
-/// Generate a key room in the map.
+/// Generate a key room in the map, returning the position of the key.
 fn gen_key_room<R: Rng>(
     rng: &mut R,
     map: &mut bandit::Map<En>,
@@ -409,7 +741,7 @@ fn gen_key_room<R: Rng>(
     occupied: &mut HashMap<Point, Cell>,
     key_clr: u32,
     ill_hosts: &[usize],
-) {
+) -> Point {
     let key_door = map_gen::gen_rect_in(
         rects,
         occupied,
@@ -423,10 +755,12 @@ fn gen_key_room<R: Rng>(
         LOG_MSGS
             .write()
             .unwrap()
-            .push(LogMsg::new(format!("Key door at {key_door}")));
+            .push(LogMsg::new(LogEvent::Other(format!("Key door at {key_door}"))));
     }
 
     let rm = rects.last().unwrap();
     let key_pos = Point::new((rm.left + rm.wid / 2) as i32, (rm.top - rm.hgt / 2) as i32);
     map.insert_tile(get_key(false, key_clr), key_pos);
+
+    key_pos
 }
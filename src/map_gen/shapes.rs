@@ -0,0 +1,320 @@
+//! Gives rooms shapes other than plain rectangles by walling off parts of them.
+
+use super::Cell;
+use crate::Point;
+use rand::Rng;
+use rect::Rect;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Chance of each cell of a cavern starting off as a wall.
+const CAVERN_FILL: f64 = 0.4;
+/// Times the cavern automaton is run.
+const CAVERN_STEPS: usize = 4;
+/// Attempts at growing a cavern before giving up.
+const CAVERN_TRIES: usize = 10;
+/// Smallest width or height inside a room that can be a cavern.
+const CAVERN_MIN: i32 = 6;
+/// Distance between pillars.
+const PILLAR_GAP: i32 = 3;
+
+/// The shapes a room can be given.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shape {
+    /// A rectangle with its corners cut off.
+    Plain,
+    /// Twisting walls grown with a cellular automaton.
+    Cavern,
+    /// One corner walled off.
+    L,
+    /// Both corners along one side walled off.
+    T,
+    /// Pillars dotted through the room.
+    Pillars,
+    /// All four corners walled off.
+    Cross,
+}
+
+/// How often each shape is used, if nothing says otherwise.
+pub const DEFAULT_MIX: [(Shape, u32); 6] = [
+    (Shape::Plain, 10),
+    (Shape::Cavern, 2),
+    (Shape::L, 2),
+    (Shape::T, 2),
+    (Shape::Pillars, 3),
+    (Shape::Cross, 2),
+];
+
+impl Shape {
+    /// Give the room with the given id this shape. Walls are never put next to doors, and every
+    /// door and floor cell of the room stays reachable from the others. Returns false, leaving the
+    /// room as it was, if the shape doesn't fit.
+    pub fn apply<R: Rng>(
+        self,
+        rect: &Rect,
+        id: usize,
+        occupied: &mut HashMap<Point, Cell>,
+        rng: &mut R,
+    ) -> bool {
+        let inner_wid = rect.wid - 2;
+        let inner_hgt = rect.hgt - 2;
+        // Width of the gaps left between walled off corners.
+        let band_wid = (inner_wid / 3).max(2);
+        let band_hgt = (inner_hgt / 3).max(2);
+
+        let walls = match self {
+            Shape::Plain => {
+                super::cut_corners(rect, occupied, rng);
+                return true;
+            }
+            Shape::Cavern => return cavern(rect, id, occupied, rng),
+            Shape::Pillars => pillars(rect, id, occupied),
+            Shape::L => corner_blocks(
+                rect,
+                &[rng.random_range(0..4)],
+                inner_wid / 2,
+                inner_hgt / 2,
+            ),
+            Shape::T => {
+                let first = rng.random_range(0..4);
+                let corners = [first, (first + 1) % 4];
+                // Corners 0 and 1 share the top, and 2 and 3 share the bottom.
+                if first % 2 == 0 {
+                    corner_blocks(rect, &corners, (inner_wid - band_wid) / 2, inner_hgt / 2)
+                } else {
+                    corner_blocks(rect, &corners, inner_wid / 2, (inner_hgt - band_hgt) / 2)
+                }
+            }
+            Shape::Cross => corner_blocks(
+                rect,
+                &[0, 1, 2, 3],
+                (inner_wid - band_wid) / 2,
+                (inner_hgt - band_hgt) / 2,
+            ),
+        };
+
+        match walls {
+            Some(walls) => fill(rect, id, occupied, walls),
+            None => false,
+        }
+    }
+}
+
+/// Returns true if the cell is part of the floor of the room with the given id.
+fn is_floor(occupied: &HashMap<Point, Cell>, p: Point, id: usize) -> bool {
+    matches!(occupied.get(&p), Some(Cell::Inner(i)) if *i == id)
+}
+
+/// Returns true if there is a door in any of the eight cells around the position.
+fn near_door(occupied: &HashMap<Point, Cell>, p: Point) -> bool {
+    p.get_all_adjacent_diagonal()
+        .into_iter()
+        .any(|adj| occupied.get(&adj).is_some_and(|cl| cl.is_door()))
+}
+
+/// Returns the inner cells of a block of the given size in each of the corners, numbered
+/// clockwise from the top left. None if the blocks don't fit or would end up next to a door.
+fn corner_blocks(rect: &Rect, corners: &[usize], wid: i32, hgt: i32) -> Option<Vec<Point>> {
+    if wid < 1 || hgt < 1 {
+        return None;
+    }
+
+    let mut cells = Vec::new();
+    for &corner in corners {
+        let x0 = if corner == 0 || corner == 3 { rect.left + 1 } else { rect.right() - wid };
+        let y0 = if corner < 2 { rect.top - 1 } else { rect.bottom() + hgt };
+        for dx in 0..wid {
+            for dy in 0..hgt {
+                cells.push(Point::new(x0 + dx, y0 - dy));
+            }
+        }
+    }
+
+    Some(cells)
+}
+
+/// Returns pillars spread through the room, each surrounded by floor.
+fn pillars(rect: &Rect, id: usize, occupied: &HashMap<Point, Cell>) -> Option<Vec<Point>> {
+    let cells: Vec<Point> = rect
+        .inner_cells()
+        .filter(|p| {
+            (p.x - rect.left) % PILLAR_GAP == 2
+                && (rect.top - p.y) % PILLAR_GAP == 2
+                && p.get_all_adjacent_diagonal()
+                    .into_iter()
+                    .all(|adj| is_floor(occupied, adj, id))
+        })
+        .collect();
+
+    if cells.is_empty() { None } else { Some(cells) }
+}
+
+/// Grow a cavern in the room, returning true if one could be made.
+fn cavern<R: Rng>(
+    rect: &Rect,
+    id: usize,
+    occupied: &mut HashMap<Point, Cell>,
+    rng: &mut R,
+) -> bool {
+    if rect.wid - 2 < CAVERN_MIN || rect.hgt - 2 < CAVERN_MIN {
+        return false;
+    }
+
+    let cells: HashSet<Point> = rect.inner_cells().filter(|p| is_floor(occupied, *p, id)).collect();
+    // Floor next to the doors is always kept, so the doors stay usable.
+    let keep: Vec<Point> = cells.iter().copied().filter(|p| near_door(occupied, *p)).collect();
+    if keep.is_empty() {
+        return false;
+    }
+
+    for _ in 0..CAVERN_TRIES {
+        let mut walls: HashSet<Point> = cells
+            .iter()
+            .copied()
+            .filter(|p| !keep.contains(p) && rng.random_bool(CAVERN_FILL))
+            .collect();
+
+        // Cells with mostly walls around them become walls, counting the edges of the room.
+        for _ in 0..CAVERN_STEPS {
+            walls = cells
+                .iter()
+                .copied()
+                .filter(|p| {
+                    let around = p
+                        .get_all_adjacent_diagonal()
+                        .into_iter()
+                        .filter(|adj| walls.contains(adj) || !cells.contains(adj))
+                        .count();
+                    !keep.contains(p) && (around >= 5 || (around >= 4 && walls.contains(p)))
+                })
+                .collect();
+        }
+
+        // Wall up any pockets that can't be reached from the doors.
+        let mut reached: HashSet<Point> = keep.iter().copied().collect();
+        let mut queue: VecDeque<Point> = keep.iter().copied().collect();
+        while let Some(p) = queue.pop_front() {
+            for adj in p.get_all_adjacent() {
+                if cells.contains(&adj) && !walls.contains(&adj) && reached.insert(adj) {
+                    queue.push_back(adj);
+                }
+            }
+        }
+
+        // Too little left to count as a room.
+        if reached.len() * 2 < cells.len() {
+            continue;
+        }
+
+        let walls = cells.iter().copied().filter(|p| !reached.contains(p)).collect();
+        if fill(rect, id, occupied, walls) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Turn the floor cells into walls, as long as they aren't next to a door and every door and
+/// floor cell of the room can still reach each other. Returns false, changing nothing, if not.
+fn fill(rect: &Rect, id: usize, occupied: &mut HashMap<Point, Cell>, walls: Vec<Point>) -> bool {
+    if walls.is_empty()
+        || walls
+            .iter()
+            .any(|p| !is_floor(occupied, *p, id) || near_door(occupied, *p))
+    {
+        return false;
+    }
+
+    for &p in &walls {
+        occupied.insert(p, Cell::Wall(vec![id]));
+    }
+
+    if connected(rect, occupied) {
+        true
+    } else {
+        for p in walls {
+            occupied.insert(p, Cell::Inner(id));
+        }
+        false
+    }
+}
+
+/// Returns true if every door and floor cell of the room can be reached from every other one.
+fn connected(rect: &Rect, occupied: &HashMap<Point, Cell>) -> bool {
+    let open = |p: &Point| rect.contains(*p) && occupied.get(p).is_some_and(|cl| !cl.is_wall());
+    let targets: Vec<Point> = rect.cells().filter(open).collect();
+    let Some(&start) = targets.first() else {
+        return false;
+    };
+
+    let mut reached = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(p) = queue.pop_front() {
+        for adj in p.get_all_adjacent() {
+            if open(&adj) && reached.insert(adj) {
+                queue.push_back(adj);
+            }
+        }
+    }
+
+    targets.iter().all(|p| reached.contains(p))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    /// Returns a room with walls around its edges and a door in the middle of its left and right
+    /// walls.
+    fn room(rect: &Rect) -> HashMap<Point, Cell> {
+        let mut occupied: HashMap<Point, Cell> = rect.cells().map(|p| (p, Cell::Wall(vec![0]))).collect();
+        for p in rect.inner_cells() {
+            occupied.insert(p, Cell::Inner(0));
+        }
+        let mid = rect.top - rect.hgt / 2;
+        occupied.insert(Point::new(rect.left, mid), Cell::Door(0, 1));
+        occupied.insert(Point::new(rect.right(), mid), Cell::Door(0, 2));
+
+        occupied
+    }
+
+    /// Returns the cells of the room that aren't walls.
+    fn open_cells(occupied: &HashMap<Point, Cell>) -> HashSet<Point> {
+        occupied.iter().filter(|(_, cl)| !cl.is_wall()).map(|(p, _)| *p).collect()
+    }
+
+    #[test]
+    fn shapes_stay_connected() {
+        let shapes = [Shape::Cavern, Shape::L, Shape::T, Shape::Pillars, Shape::Cross];
+        for seed in 0..64 {
+            let mut rng = rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(seed);
+            for (wid, hgt) in [(7, 7), (12, 9), (15, 15)] {
+                let rect = Rect::new(0, hgt, wid, hgt);
+                for shape in shapes {
+                    let mut occupied = room(&rect);
+                    let before = open_cells(&occupied);
+
+                    if shape.apply(&rect, 0, &mut occupied, &mut rng) {
+                        assert!(connected(&rect, &occupied), "{shape:?} split a {wid}x{hgt} room");
+                    } else {
+                        assert_eq!(open_cells(&occupied), before, "{shape:?} changed a room it failed on");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn connected_finds_splits() {
+        let rect = Rect::new(0, 8, 10, 8);
+        let mut occupied = room(&rect);
+        assert!(connected(&rect, &occupied));
+
+        // A wall from top to bottom cuts the doors off from each other.
+        for y in rect.bottom()..=rect.top {
+            occupied.insert(Point::new(5, y), Cell::Wall(vec![0]));
+        }
+        assert!(!connected(&rect, &occupied));
+    }
+}
@@ -0,0 +1,433 @@
+//! Checks generated floors for anything that would make them unfair or impossible to play.
+
+use super::bandit_gen::{FloorInfo, gen_run};
+use crate::entity::{En, EntityTemplate, KEY_CLRS_COUNT, LOG_MSGS};
+use crate::templates::{self, metadata::TempMeta};
+use crate::{KILL_SCREEN, Mechanism, Point, Tile, bn};
+use rect::Rect;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+/// Something wrong with a generated floor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Problem {
+    /// There is no player on the floor.
+    NoPlayer,
+    /// A door with fewer than two walls next to it, so it isn't really in a wall.
+    SusDoor(Point),
+    /// A door with more than two walls next to it, so it can't be walked through.
+    ImpassableDoor(Point),
+    /// A door with other doors to its left and below it.
+    DoorHell(Point),
+    /// The room with the given id can't be reached, even after unlocking every door possible.
+    UnreachableRoom(usize),
+    /// A key that can only be reached by going through a locked door.
+    KeyBehindLock(Point),
+    /// A locked door that the player runs out of keys before reaching.
+    StuckLock(Point),
+    /// An exit inside a wall or right next to a door.
+    MisplacedExit(Point),
+    /// An exit that can be reached without unlocking anything.
+    UnlockedExit(Point),
+    /// An exit that can't be reached, even after unlocking every door possible.
+    UnreachableExit(Point),
+    /// An enemy standing in a doorway.
+    EnemyOnDoor(Point),
+    /// An enemy stuck inside a wall.
+    EnemyInWall(Point),
+    /// An ice room with a door that can't be slid to from another of its doors.
+    UnsolvableIce(usize),
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::NoPlayer => write!(f, "no player"),
+            Problem::SusDoor(p) => write!(f, "sus door at {p}"),
+            Problem::ImpassableDoor(p) => write!(f, "impassable door at {p}"),
+            Problem::DoorHell(p) => write!(f, "door hell at {p}"),
+            Problem::UnreachableRoom(id) => write!(f, "room {id} is unreachable"),
+            Problem::KeyBehindLock(p) => write!(f, "key at {p} is behind a locked door"),
+            Problem::StuckLock(p) => write!(f, "not enough keys to open the door at {p}"),
+            Problem::MisplacedExit(p) => write!(f, "exit at {p} is in a wall or by a door"),
+            Problem::UnlockedExit(p) => write!(f, "exit at {p} isn't behind a locked door"),
+            Problem::UnreachableExit(p) => write!(f, "exit at {p} is unreachable"),
+            Problem::EnemyOnDoor(p) => write!(f, "enemy on the door at {p}"),
+            Problem::EnemyInWall(p) => write!(f, "enemy in the wall at {p}"),
+            Problem::UnsolvableIce(id) => write!(f, "ice room {id} can't be solved"),
+        }
+    }
+}
+
+/// A problem found on one floor of the run generated from a seed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Report {
+    pub seed: u64,
+    pub floor: u32,
+    pub problem: Problem,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:X} floor {}: {}", self.seed, self.floor, self.problem)
+    }
+}
+
+/// Returns true if the tile can be walked over as it is. Locked doors and closed gates have to be
+/// opened first, and cracked walls can't be counted on to be blown up.
+fn passable(t: &Tile) -> bool {
+    !t.blocking
+}
+
+/// Returns every position that can be walked to from the start, also going through the given
+/// locked doors and gates.
+fn flood(map: &bn::Map<En>, start: Point, opened: &HashSet<Point>) -> HashSet<Point> {
+    let mut reached = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(p) = queue.pop_front() {
+        for adj in p.get_all_adjacent() {
+            if map
+                .get_map(adj)
+                .is_some_and(|t| passable(t) || opened.contains(&adj))
+                && reached.insert(adj)
+            {
+                queue.push_back(adj);
+            }
+        }
+    }
+
+    reached
+}
+
+/// A gate and the pressure plate that opens it.
+#[derive(Clone, Copy)]
+struct Gate {
+    pos: Point,
+    plate: Point,
+    /// Whether the plate opens the gate for good, rather than only while something stands on it.
+    latch: bool,
+}
+
+/// Returns every position that can be walked to from the start without any keys, opening gates
+/// whenever their plate can be reached. Gates that are only held open need something other than
+/// the player left on the plate, so they also need an enemy in reach to lure onto it.
+fn flood_gates(
+    map: &bn::Map<En>,
+    start: Point,
+    opened: &mut HashSet<Point>,
+    gates: &[Gate],
+    enemies: &[Point],
+) -> HashSet<Point> {
+    loop {
+        let reached = flood(map, start, opened);
+        let lure = enemies.iter().any(|e| reached.contains(e));
+        let before = opened.len();
+        opened.extend(
+            gates
+                .iter()
+                .filter(|g| reached.contains(&g.plate) && (g.latch || lure))
+                .map(|g| g.pos),
+        );
+        if opened.len() == before {
+            return reached;
+        }
+    }
+}
+
+/// Returns where sliding from the position in the given direction ends up. Slides stop in front
+/// of walls and the edges of the room, and on doors (even locked ones) and anything that isn't ice.
+fn slide(map: &bn::Map<En>, rm: &Rect, pos: Point, dir: Point) -> Point {
+    let mut cur = pos;
+    loop {
+        let nx = cur + dir;
+        match map.get_map(nx) {
+            Some(t) if rm.contains(nx) && (!t.blocking || t.door) => {
+                if t.slippery && !t.door {
+                    cur = nx;
+                } else {
+                    return nx;
+                }
+            }
+            _ => return cur,
+        }
+    }
+}
+
+/// Returns true if every door of the ice room can be slid to from every other one.
+fn ice_solvable(map: &bn::Map<En>, rm: &Rect) -> bool {
+    let doors: Vec<Point> = rm.cells().filter(|p| map.get_map(*p).is_some_and(|t| t.door)).collect();
+
+    doors.iter().all(|&d| {
+        let mut reached = HashSet::from([d]);
+        let mut queue = VecDeque::from([d]);
+        while let Some(p) = queue.pop_front() {
+            for dir in Point::ORIGIN.get_all_adjacent() {
+                let nx = slide(map, rm, p, dir);
+                if reached.insert(nx) {
+                    queue.push_back(nx);
+                }
+            }
+        }
+        doors.iter().all(|o| reached.contains(o))
+    })
+}
+
+/// Check a generated floor, returning everything wrong with it. Keys holds how many keys of each
+/// colour the player arrives with, and is left holding how many they leave with.
+pub fn check_floor(
+    map: &bn::Map<En>,
+    info: &FloorInfo,
+    keys: &mut [u32; KEY_CLRS_COUNT],
+) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let Some(start) = map.get_entities().find(|(_, e)| e.is_player).map(|(p, _)| *p) else {
+        return vec![Problem::NoPlayer];
+    };
+
+    let cells: HashSet<Point> = info.rooms.iter().flat_map(|r| r.cells()).collect();
+    let is_door = |p: Point| map.get_map(p).is_some_and(|t| t.door);
+
+    // Every door should have exactly two walls next to it.
+    let mut cells_sorted: Vec<Point> = cells.iter().copied().collect();
+    cells_sorted.sort_by_key(|p| (p.y, p.x));
+    for &p in &cells_sorted {
+        if !is_door(p) {
+            continue;
+        }
+
+        // Locked doors in a row don't count as walls for each other.
+        let wall_count = p
+            .get_all_adjacent()
+            .into_iter()
+            .filter(|adj| map.get_map(*adj).is_some_and(|t| t.blocking && !t.door))
+            .count();
+        match wall_count {
+            0 | 1 => problems.push(Problem::SusDoor(p)),
+            3 | 4 => problems.push(Problem::ImpassableDoor(p)),
+            _ => (),
+        }
+
+        if is_door(p + Point::new(-1, 0)) && is_door(p + Point::new(0, -1)) {
+            problems.push(Problem::DoorHell(p));
+        }
+    }
+
+    // Each lock and the id of the keys that open it, and each gate and the plate that opens it.
+    let locks: Vec<(Point, u32)> = cells_sorted
+        .iter()
+        .filter_map(|p| map.get_map(*p).and_then(|t| t.locked).map(|id| (*p, id)))
+        .collect();
+    let gates: Vec<Gate> = cells_sorted
+        .iter()
+        .filter_map(|p| match map.get_map(*p).and_then(|t| t.mechanism) {
+            Some(Mechanism::Gate(Some(plate))) if map.get_map(*p).is_some_and(|t| t.blocking) => {
+                let latch = map
+                    .get_map(plate)
+                    .is_some_and(|t| matches!(t.mechanism, Some(Mechanism::Latch(_))));
+                Some(Gate { pos: *p, plate, latch })
+            }
+            _ => None,
+        })
+        .collect();
+    let key_idx = |id: u32| id as usize % KEY_CLRS_COUNT;
+
+    let mut enemies: Vec<Point> = map
+        .get_entities()
+        .filter(|(_, e)| !e.is_player)
+        .map(|(p, _)| *p)
+        .collect();
+    enemies.sort_by_key(|p| (p.y, p.x));
+
+    // Keys that can't be picked up before unlocking anything are behind a lock.
+    let mut opened = HashSet::new();
+    let first = flood_gates(map, start, &mut opened, &gates, &enemies);
+    for &(k, _) in &info.keys {
+        if !first.contains(&k) {
+            problems.push(Problem::KeyBehindLock(k));
+        }
+    }
+
+    // Pick up keys and open locked doors as they are reached, for as long as the right keys last.
+    let mut collected = HashSet::new();
+    let mut reached = first.clone();
+    loop {
+        for &(k, id) in &info.keys {
+            if reached.contains(&k) && collected.insert(k) {
+                keys[key_idx(id)] += 1;
+            }
+        }
+
+        let Some(&(lock, id)) = locks.iter().find(|(l, id)| {
+            !opened.contains(l)
+                && keys[key_idx(*id)] > 0
+                && l.get_all_adjacent().into_iter().any(|p| reached.contains(&p))
+        }) else {
+            break;
+        };
+        opened.insert(lock);
+        keys[key_idx(id)] -= 1;
+        reached = flood_gates(map, start, &mut opened, &gates, &enemies);
+    }
+    for &(l, _) in locks.iter().filter(|(l, _)| !opened.contains(l)) {
+        problems.push(Problem::StuckLock(l));
+    }
+
+    for (id, rm) in info.rooms.iter().enumerate() {
+        if !rm.inner_cells().any(|p| reached.contains(&p)) {
+            problems.push(Problem::UnreachableRoom(id));
+        }
+        if rm.inner_cells().any(|p| map.get_map(p).is_some_and(|t| t.slippery))
+            && !ice_solvable(map, rm)
+        {
+            problems.push(Problem::UnsolvableIce(id));
+        }
+    }
+
+    if let Some(exit) = info.exit {
+        let by_door = exit
+            .get_all_adjacent()
+            .into_iter()
+            .any(|p| map.get_map(p).is_some_and(|t| t.door && t.locked.is_none()));
+        if map.get_map(exit).is_none_or(|t| t.blocking) || by_door {
+            problems.push(Problem::MisplacedExit(exit));
+        }
+        if !locks.is_empty() && first.contains(&exit) {
+            problems.push(Problem::UnlockedExit(exit));
+        }
+        if !reached.contains(&exit) {
+            problems.push(Problem::UnreachableExit(exit));
+        }
+    }
+
+    for p in enemies {
+        match map.get_map(p) {
+            Some(t) if t.door => problems.push(Problem::EnemyOnDoor(p)),
+            Some(t) if t.blocking => problems.push(Problem::EnemyInWall(p)),
+            _ => (),
+        }
+    }
+
+    problems
+}
+
+/// Generate every floor of the run from the seed and check each one.
+pub fn check_seed(
+    seed: u64,
+    meta: &HashMap<char, TempMeta>,
+    templates: &[EntityTemplate],
+    elites: &[EntityTemplate],
+) -> Vec<Report> {
+    let mut reports = Vec::new();
+    // Keys left over from one floor can be used on the next.
+    let mut keys = [0; KEY_CLRS_COUNT];
+    gen_run(seed, KILL_SCREEN as u32 - 1, meta, templates, elites, |floor, map, info| {
+        reports.extend(
+            check_floor(map, info, &mut keys)
+                .into_iter()
+                .map(|problem| Report { seed, floor, problem }),
+        );
+    });
+    // Nobody is going to read the floor entered messages.
+    LOG_MSGS.write().unwrap().clear();
+
+    reports
+}
+
+/// Check every floor of the given number of seeds, starting from the initial seed.
+pub fn check_seeds(init_seed: u64, sds: u64) -> Vec<Report> {
+    let meta = templates::metadata::get_metadata();
+    let (templates, elites) = templates::get_templates();
+
+    (init_seed..init_seed + sds)
+        .flat_map(|sd| check_seed(sd, &meta, &templates, &elites))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map_gen::bandit_gen::FloorInfo;
+    use crate::themes::Theme;
+    use crate::tile_presets;
+
+    /// Returns two rooms side by side sharing a wall, with the player in the left one and the exit
+    /// in the right one. The tile given is put in the middle of the shared wall.
+    fn two_rooms(between: Tile) -> (bn::Map<En>, FloorInfo) {
+        let left = Rect::new(0, 4, 5, 5);
+        let right = Rect::new(4, 4, 5, 5);
+        let mut map = bn::Map::new(0, 0);
+        for rm in [left, right] {
+            for p in rm.cells() {
+                let blocking = !rm.inner_cells().any(|q| q == p);
+                map.insert_tile(Tile { empt: false, blocking, ..Tile::default() }, p);
+            }
+        }
+        map.insert_tile(between, Point::new(4, 2));
+        map.insert_entity(templates::get_player(), Point::new(1, 1));
+
+        let info = FloorInfo {
+            rooms: vec![left, right],
+            exit: Some(Point::new(7, 2)),
+            keys: Vec::new(),
+            theme: Theme::Halls,
+        };
+        (map, info)
+    }
+
+    #[test]
+    fn keys_match_locks() {
+        // The right key opens the way.
+        let (mut map, mut info) = two_rooms(tile_presets::get_locked_door(false, 1));
+        map.insert_tile(tile_presets::get_key(false, 5), Point::new(2, 3));
+        info.keys.push((Point::new(2, 3), 5));
+        let mut keys = [0; KEY_CLRS_COUNT];
+        let problems = check_floor(&map, &info, &mut keys);
+        assert!(problems.is_empty(), "{problems:?}");
+        assert_eq!(keys, [0; KEY_CLRS_COUNT]);
+
+        // Keys of another colour don't, but are kept for later.
+        info.keys[0].1 = 2;
+        let mut keys = [0; KEY_CLRS_COUNT];
+        assert_eq!(
+            check_floor(&map, &info, &mut keys),
+            [
+                Problem::StuckLock(Point::new(4, 2)),
+                Problem::UnreachableRoom(1),
+                Problem::UnreachableExit(Point::new(7, 2)),
+            ]
+        );
+        assert_eq!(keys, [0, 0, 1, 0]);
+
+        // Keys brought from the last floor can be used.
+        info.keys.clear();
+        let mut keys = [0, 1, 0, 0];
+        let problems = check_floor(&map, &info, &mut keys);
+        assert!(problems.is_empty(), "{problems:?}");
+    }
+
+    #[test]
+    fn walls_and_gates() {
+        // Cracked walls can't be counted on to be blown up.
+        let (map, info) = two_rooms(tile_presets::get_cracked_wall(false));
+        let problems = check_floor(&map, &info, &mut [0; KEY_CLRS_COUNT]);
+        assert!(problems.contains(&Problem::UnreachableRoom(1)));
+
+        // Gates open once their plate can be reached.
+        let (mut map, info) = two_rooms(tile_presets::get_gate(false, Some(Point::new(1, 3))));
+        map.insert_tile(tile_presets::get_latch_plate(false, Some(Point::new(4, 2))), Point::new(1, 3));
+        assert!(!check_floor(&map, &info, &mut [0; KEY_CLRS_COUNT]).contains(&Problem::UnreachableRoom(1)));
+
+        let (mut map, info) = two_rooms(tile_presets::get_gate(false, Some(Point::new(6, 3))));
+        map.insert_tile(tile_presets::get_latch_plate(false, Some(Point::new(4, 2))), Point::new(6, 3));
+        assert!(check_floor(&map, &info, &mut [0; KEY_CLRS_COUNT]).contains(&Problem::UnreachableRoom(1)));
+
+        // Gates that are only held open need an enemy left on the plate.
+        let (mut map, info) = two_rooms(tile_presets::get_gate(false, Some(Point::new(1, 3))));
+        map.insert_tile(tile_presets::get_plate(false, Some(Point::new(4, 2))), Point::new(1, 3));
+        assert!(check_floor(&map, &info, &mut [0; KEY_CLRS_COUNT]).contains(&Problem::UnreachableRoom(1)));
+
+        let enemy = En::from_template(&templates::get_templates().0[0], false, false);
+        map.insert_entity(enemy, Point::new(3, 1));
+        assert!(!check_floor(&map, &info, &mut [0; KEY_CLRS_COUNT]).contains(&Problem::UnreachableRoom(1)));
+    }
+}
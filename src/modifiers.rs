@@ -0,0 +1,171 @@
+//! Optional rules that change how a run plays, chosen before it starts.
+
+use crate::entity::PUZZLE;
+use std::{fmt, sync::RwLock};
+
+/// Modifiers chosen for the current run.
+pub static MODIFIERS: RwLock<Modifiers> = RwLock::new(Modifiers::NONE);
+
+/// A single rule that can be turned on for a run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Modifier {
+    /// The player has 1 hp, as in puzzles.
+    OneHp,
+    /// Every room has twice the budget for enemies.
+    DoubleBudget,
+    /// No ice is generated.
+    NoIce,
+    /// Rooms can be filled with elites on every floor, not just floor 4.
+    EliteFloors,
+    /// Only the room the player is standing in can be seen.
+    Fog,
+    /// The player isn't healed after getting past a floor's boss.
+    NoHealing,
+}
+
+impl Modifier {
+    /// Every modifier, in the order they are shown and stored in run codes.
+    pub const ALL: [Modifier; 6] = [
+        Modifier::OneHp,
+        Modifier::DoubleBudget,
+        Modifier::NoIce,
+        Modifier::EliteFloors,
+        Modifier::Fog,
+        Modifier::NoHealing,
+    ];
+
+    /// Returns the bit used to store this modifier.
+    fn bit(self) -> u8 {
+        1 << Self::ALL.iter().position(|m| *m == self).unwrap()
+    }
+
+    /// Returns a two character tag for this modifier, used where there isn't much space.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Modifier::OneHp => "1H",
+            Modifier::DoubleBudget => "2B",
+            Modifier::NoIce => "NI",
+            Modifier::EliteFloors => "EF",
+            Modifier::Fog => "FG",
+            Modifier::NoHealing => "NH",
+        }
+    }
+
+    /// Returns what the score is multiplied by while this modifier is active.
+    pub fn score_mult(self) -> f64 {
+        match self {
+            Modifier::OneHp => 2.0,
+            Modifier::DoubleBudget => 1.5,
+            Modifier::NoIce => 0.9,
+            Modifier::EliteFloors => 1.5,
+            Modifier::Fog => 1.25,
+            Modifier::NoHealing => 1.25,
+        }
+    }
+}
+
+impl fmt::Display for Modifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Modifier::OneHp => "One HP",
+            Modifier::DoubleBudget => "Double Budget",
+            Modifier::NoIce => "No Ice",
+            Modifier::EliteFloors => "Elite Floors",
+            Modifier::Fog => "Fog",
+            Modifier::NoHealing => "No Healing",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A set of modifiers, stored as one bit each.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers(u8);
+
+impl Modifiers {
+    /// No modifiers at all.
+    pub const NONE: Modifiers = Modifiers(0);
+
+    /// Returns true if the modifier is in the set.
+    pub fn has(&self, m: Modifier) -> bool {
+        self.0 & m.bit() != 0
+    }
+
+    /// Add or remove a modifier from the set.
+    pub fn set(&mut self, m: Modifier, on: bool) {
+        if on {
+            self.0 |= m.bit();
+        } else {
+            self.0 &= !m.bit();
+        }
+    }
+
+    /// Returns true if there are no modifiers in the set.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns every modifier in the set.
+    pub fn iter(&self) -> impl Iterator<Item = Modifier> + '_ {
+        Modifier::ALL.into_iter().filter(|m| self.has(*m))
+    }
+
+    /// Returns what the score is multiplied by with every modifier in the set.
+    pub fn score_mult(&self) -> f64 {
+        self.iter().map(|m| m.score_mult()).product()
+    }
+
+    /// Returns the tags of every modifier in the set, separated by spaces.
+    pub fn tags(&self) -> String {
+        self.iter().map(|m| m.tag()).collect::<Vec<_>>().join(" ")
+    }
+
+    /// Returns a code that can be shared to play the same run again. Runs without modifiers are
+    /// just the seed.
+    pub fn run_code(&self, seed: u64) -> String {
+        if self.is_empty() {
+            format!("{seed:X}")
+        } else {
+            format!("{seed:X}-{:02X}", self.0)
+        }
+    }
+
+    /// Split a run code into its seed and modifiers. Returns None if it isn't a run code with
+    /// modifiers.
+    pub fn from_run_code(code: &str) -> Option<(u64, Modifiers)> {
+        let (seed, mods) = code.split_once('-')?;
+        let seed = u64::from_str_radix(seed, 16).ok()?;
+        let mods = u8::from_str_radix(mods, 16).ok()?;
+
+        // Ignore bits that don't belong to a modifier.
+        let all = Modifier::ALL.iter().fold(0, |acc, m| acc | m.bit());
+        Some((seed, Modifiers(mods & all)))
+    }
+}
+
+/// Returns true if the modifier is active. Modifiers never apply to puzzles.
+pub fn active(m: Modifier) -> bool {
+    unsafe { PUZZLE.is_none() } && MODIFIERS.read().unwrap().has(m)
+}
+
+/// Returns what the score of the current run is multiplied by.
+pub fn score_mult() -> f64 {
+    MODIFIERS.read().unwrap().score_mult()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_code_round_trip() {
+        let seed = 0xDEAD_BEEF_0123;
+        // Every combination of modifiers, not counting the empty set.
+        for bits in 1..1u8 << Modifier::ALL.len() {
+            let mods = Modifiers(bits);
+            assert_eq!(Modifiers::from_run_code(&mods.run_code(seed)), Some((seed, mods)));
+        }
+        // Runs without modifiers are just the seed.
+        assert_eq!(Modifiers::from_run_code(&Modifiers::NONE.run_code(seed)), None);
+    }
+}
@@ -0,0 +1,240 @@
+//! Full screen map of everything that has been revealed on the current floor.
+
+use crate::{DOOR_CHAR, Point, REVEALED, Tile, bn::windowed, glyphs, print_win};
+use crate::bn::{self, Tile as _};
+use crate::camera::room_revealed;
+use crate::entity::{DEPTH, En, PLAYER, Special};
+use crate::layout::Layout;
+use crate::map_gen::bandit_gen::FloorInfo;
+use crate::travel::unexplored_doors;
+use crossterm::style::{self, Stylize};
+use crossterm::{event, execute, terminal};
+use rect::Rect;
+use std::io;
+
+type StyleCh = style::StyledContent<char>;
+
+/// Rows at the top of the screen used by the title.
+const HEADER_HGT: usize = 2;
+/// Rows at the bottom of the screen used by the legend.
+const FOOTER_HGT: usize = 2;
+
+/// Colour of the floor of rooms with no enemies left in them.
+const CLEARED_CLR: style::Color = style::Color::DarkGreen;
+/// Colour of the floor of rooms that still have enemies in them.
+const UNCLEARED_CLR: style::Color = style::Color::Red;
+
+/// Turn some text into a line of the given colour that is exactly wid characters long.
+fn line(clr: style::Color, txt: &str, wid: usize) -> Vec<StyleCh> {
+    let mut ln: Vec<StyleCh> = std::iter::once(' '.stylize())
+        .chain(txt.chars().map(|ch| ch.with(clr)))
+        .take(wid)
+        .collect();
+    ln.resize(wid, ' '.stylize());
+    ln
+}
+
+/// Returns true if any enemies are still in the room.
+fn has_enemies(map: &bn::Map<En>, r: &Rect) -> bool {
+    r.cells().into_iter().any(|p| {
+        map.get_ent(p)
+            .is_some_and(|e| !e.is_player && e.special != Special::WallSentry)
+    })
+}
+
+/// Returns how important it is to show what is at a position when several tiles are squashed
+/// into one character, along with the character to show.
+fn mark(
+    map: &bn::Map<En>,
+    info: &FloorInfo,
+    doors: &[Point],
+    cleared: Option<bool>,
+    pos: Point,
+    t: &Tile,
+) -> (u32, StyleCh) {
+    if pos == unsafe { PLAYER } {
+        let ch = map.get_ent(pos).map(|e| e.ch).unwrap_or('@'.stylize());
+        return (6, ch);
+    }
+    if info.exit == Some(pos) {
+        return (5, t.repr());
+    }
+    if info.keys.iter().any(|(p, _)| *p == pos) && t.step_effect.is_some() {
+        return (5, t.repr());
+    }
+    if t.locked.is_some() {
+        return (4, t.repr());
+    }
+    if doors.contains(&pos) {
+        return (4, '?'.yellow());
+    }
+    if t.door {
+        return (3, DOOR_CHAR.with(crate::get_door_clr()));
+    }
+    if t.blocking {
+        return (2, t.repr());
+    }
+    if t.empt {
+        return (0, ' '.stylize());
+    }
+
+    let ch = match t.ch {
+        Some(ch) => ch,
+        None => '.'.stylize(),
+    };
+    match cleared {
+        Some(true) => (1, ch.with(CLEARED_CLR)),
+        Some(false) => (1, ch.with(UNCLEARED_CLR)),
+        None => (1, ch),
+    }
+}
+
+/// Draw the revealed parts of the floor so that they fit in the given space, squashing several
+/// tiles into one character if the floor is too big.
+pub fn draw(map: &bn::Map<En>, info: &FloorInfo, wid: usize, hgt: usize) -> Vec<Vec<StyleCh>> {
+    let see_all = *REVEALED.read().unwrap();
+    let rooms: Vec<(Rect, bool)> = info
+        .rooms
+        .iter()
+        .filter(|r| see_all || room_revealed(map, r))
+        .map(|r| (*r, !has_enemies(map, r)))
+        .collect();
+
+    let Some(first) = rooms.first() else {
+        return vec![line(style::Color::DarkGrey, "Nothing has been revealed", wid)];
+    };
+
+    // Corners of the box around every revealed room.
+    let mut min = Point::new(first.0.left, first.0.bottom());
+    let mut max = Point::new(first.0.right(), first.0.top);
+    for (r, _) in &rooms {
+        min = Point::new(min.x.min(r.left), min.y.min(r.bottom()));
+        max = Point::new(max.x.max(r.right()), max.y.max(r.top));
+    }
+
+    // Tiles squashed into each character along both axes.
+    let ext_wid = (max.x - min.x + 1) as usize;
+    let ext_hgt = (max.y - min.y + 1) as usize;
+    let scale = ext_wid.div_ceil(wid.max(1)).max(ext_hgt.div_ceil(hgt.max(1))).max(1);
+
+    let doors = unexplored_doors(map, &info.rooms);
+    let mut grid = Vec::new();
+
+    for row in 0..ext_hgt.div_ceil(scale) {
+        let mut ln = Vec::new();
+        for col in 0..ext_wid.div_ceil(scale) {
+            let mut best = (0, ' '.stylize());
+
+            for dy in 0..scale {
+                for dx in 0..scale {
+                    let pos = Point::new(
+                        min.x + (col * scale + dx) as i32,
+                        max.y - (row * scale + dy) as i32,
+                    );
+                    let Some(t) = map.get_map(pos) else {
+                        continue;
+                    };
+                    if !t.revealed && !see_all {
+                        continue;
+                    }
+
+                    let cleared = rooms
+                        .iter()
+                        .find(|(r, _)| r.contains(pos))
+                        .map(|(_, c)| *c);
+                    let cur = mark(map, info, &doors, cleared, pos, t);
+                    if cur.0 > best.0 {
+                        best = cur;
+                    }
+                }
+            }
+            ln.push(best.1);
+        }
+        grid.push(ln);
+    }
+
+    grid
+}
+
+/// Show a map of the revealed parts of the floor until the player closes it. The screen is
+/// cleared afterwards, so anything that was being shown needs printing again.
+pub fn view_overview(map: &bn::Map<En>, info: &FloorInfo) {
+    let mut lay = Layout::wait_for_fit();
+    let mut cont = windowed::Container::new();
+    cont.add_win(windowed::Window::new(Point::ORIGIN));
+    let mut handle = io::stdout();
+    let _ = execute!(handle, terminal::Clear(terminal::ClearType::All));
+
+    loop {
+        let wid = lay.wid as usize;
+        let rows = (lay.hgt as usize).saturating_sub(HEADER_HGT + FOOTER_HGT).max(1);
+        let grid = draw(map, info, wid - 2, rows);
+
+        let data = &mut cont.windows[0].data;
+        data.clear();
+        data.push(line(
+            style::Color::White,
+            &format!("FLOOR {}", unsafe { DEPTH }),
+            wid,
+        ));
+        data.push(line(style::Color::White, "", wid));
+
+        // Centre the map in the space available.
+        let left = (wid - grid.first().map(|g| g.len()).unwrap_or(0)) / 2;
+        let top = (rows - grid.len().min(rows)) / 2;
+        for y in 0..rows {
+            let mut ln = vec![' '.stylize(); wid];
+            if y >= top
+                && let Some(g) = grid.get(y - top)
+            {
+                for (x, ch) in g.iter().enumerate() {
+                    ln[left + x] = *ch;
+                }
+            }
+            data.push(ln);
+        }
+
+        let mut legend = line(style::Color::White, "", wid);
+        let parts: [(StyleCh, &str); 5] = [
+            ('.'.with(CLEARED_CLR), "cleared"),
+            ('.'.with(UNCLEARED_CLR), "enemies"),
+            ('?'.yellow(), "unexplored"),
+            (glyphs().key.stylize(), "key"),
+            ('>'.stylize(), "exit"),
+        ];
+        let mut x = 1;
+        for (ch, txt) in parts {
+            let txt = format!(" {txt}  ");
+            if x + txt.len() + 1 > wid {
+                break;
+            }
+            legend[x] = ch;
+            for (n, c) in txt.chars().enumerate() {
+                legend[x + 1 + n] = c.stylize();
+            }
+            x += txt.len() + 1;
+        }
+        data.push(line(style::Color::White, "", wid));
+        data.push(legend);
+
+        cont.refresh();
+        print_win(&cont);
+
+        match event::read().expect("what") {
+            event::Event::Key(ke) if ke.is_press() => {
+                if matches!(ke.code, event::KeyCode::Esc | event::KeyCode::Char('o')) {
+                    break;
+                }
+            }
+            event::Event::Resize(..) => {
+                lay = Layout::wait_for_fit();
+                cont = windowed::Container::new();
+                cont.add_win(windowed::Window::new(Point::ORIGIN));
+                let _ = execute!(handle, terminal::Clear(terminal::ClearType::All));
+            }
+            _ => (),
+        }
+    }
+
+    let _ = execute!(handle, terminal::Clear(terminal::ClearType::All));
+}
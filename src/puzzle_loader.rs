@@ -137,13 +137,25 @@ fn create_map(data: &str, tile_set: &ts::TileSet, default_tile: &Tile) -> Puzzle
         .id
         .replace(u128::from_ne_bytes(md5::compute(data).0));
 
+    let mut plates = Vec::new();
+    let mut gates = Vec::new();
+    let mut teleporters = Vec::new();
+
     for (y, ln) in data.lines().rev().enumerate() {
         for (x, ch) in ln.chars().enumerate() {
             let pos = Point::new(x as i32, y as i32);
 
             if let Some(obj) = tile_set.map(ch) {
                 match obj {
-                    ts::BanditObj::Tile(t) => map.insert_tile(t.clone(), pos),
+                    ts::BanditObj::Tile(t) => {
+                        match t.mechanism {
+                            Some(Mechanism::Plate(_)) => plates.push(pos),
+                            Some(Mechanism::Gate(_)) => gates.push(pos),
+                            Some(Mechanism::Teleporter(_)) => teleporters.push(pos),
+                            _ => (),
+                        }
+                        map.insert_tile(t.clone(), pos)
+                    }
                     ts::BanditObj::En(en) => {
                         if en.is_player {
                             builder.pl_pos.replace(pos);
@@ -156,10 +168,35 @@ fn create_map(data: &str, tile_set: &ts::TileSet, default_tile: &Tile) -> Puzzle
         }
     }
 
+    link_mechanisms(&mut map, &plates, &gates, &teleporters);
+
     builder.data.replace(map);
     builder
 }
 
+/// Connects each pressure plate to the nearest gate, and each teleporter to the one found after
+/// it, starting from the bottom row. A gate should only have one plate, and a teleporter without
+/// a partner does nothing.
+fn link_mechanisms(
+    map: &mut bn::Map<entity::En>,
+    plates: &[Point],
+    gates: &[Point],
+    teleporters: &[Point],
+) {
+    for &plate in plates {
+        let Some(&gate) = gates.iter().min_by_key(|g| g.dist_squared(plate)) else {
+            break;
+        };
+        map.get_map_mut(plate).unwrap().mechanism = Some(Mechanism::Plate(Some(gate)));
+        map.get_map_mut(gate).unwrap().mechanism = Some(Mechanism::Gate(Some(plate)));
+    }
+
+    for pair in teleporters.chunks_exact(2) {
+        map.get_map_mut(pair[0]).unwrap().mechanism = Some(Mechanism::Teleporter(Some(pair[1])));
+        map.get_map_mut(pair[1]).unwrap().mechanism = Some(Mechanism::Teleporter(Some(pair[0])));
+    }
+}
+
 /// Uses the given tileset to turn a string into a puzzle. Unknown characters will be ignored.
 pub fn load_pzl(
     data: &str,
@@ -1,3 +1,4 @@
+use super::classes::Class;
 use super::puzzle_loader::{LoadErr, pzl_save, read_lines};
 use pzl_save::get_save_path;
 use std::{
@@ -9,6 +10,25 @@ use std::{
 const SCORE_FILE: &str = "high_score.txt";
 const KILLS_FILE: &str = "kills.txt";
 const WON_YET_FILE: &str = "won_yet.txt";
+const LOG_FILE: &str = "run_log.txt";
+const ENDLESS_FILE: &str = "endless.txt";
+const CLASS_SCORES_FILE: &str = "class_scores.txt";
+
+/// Number of endless runs kept on the leaderboard.
+pub const ENDLESS_RECORDS: usize = 10;
+
+/// How far an endless run got.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EndlessRecord {
+    /// Floors descended.
+    pub depth: u32,
+    /// Score at the end of the run.
+    pub score: f64,
+    /// Seed the run was played on.
+    pub seed: u64,
+    /// Class the run was played as.
+    pub class: Class,
+}
 
 /// Get the high score from the save file.
 pub fn load_highscore() -> Result<f64, LoadErr> {
@@ -134,3 +154,132 @@ pub fn save_kills(kills: &HashMap<char, u32>) {
     file.flush().expect("Couldn't write to kills file");
 }
 
+/// Write the lines of a run's log to a file, returning where it was written.
+pub fn save_log(lines: &[String]) -> io::Result<std::path::PathBuf> {
+    let save = get_save_path();
+
+    fs::create_dir_all(&save)?;
+    let path = save.join(LOG_FILE);
+    let mut file = io::BufWriter::new(fs::File::create(&path)?);
+
+    for ln in lines {
+        file.write_all(format!("{ln}\n").as_bytes())?;
+    }
+    file.flush()?;
+
+    Ok(path)
+}
+
+/// Get the endless mode leaderboard from the file, deepest first.
+pub fn load_endless() -> Result<Vec<EndlessRecord>, LoadErr> {
+    let mut records = Vec::new();
+
+    for ln in read_lines(get_save_path().join(ENDLESS_FILE))
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => LoadErr::NotFound,
+            io::ErrorKind::ResourceBusy => {
+                LoadErr::Cant(String::from("the file is already in use"))
+            }
+            e => LoadErr::Other(e),
+        })?
+        .map_while(Result::ok)
+    {
+        let vals: Vec<&str> = ln.split(':').collect();
+        // Records from before classes were added don't have one, so were played as a fighter.
+        let (depth, score, seed, class) = match vals[..] {
+            [depth, score, seed] => (depth, score, seed, Class::Fighter.tag()),
+            [depth, score, seed, class] => (depth, score, seed, class),
+            _ => {
+                return Err(LoadErr::IncorrectFormat(format!("'{ln}' is not depth:score:seed:class")));
+            }
+        };
+
+        records.push(EndlessRecord {
+            depth: depth.parse().map_err(|_| LoadErr::IncorrectFormat(format!("'{depth}' is not a depth")))?,
+            score: score.parse().map_err(|_| LoadErr::IncorrectFormat(format!("'{score}' is not a score")))?,
+            seed: u64::from_str_radix(seed, 16).map_err(|_| LoadErr::IncorrectFormat(format!("'{seed}' is not a seed")))?,
+            class: Class::from_tag(class).ok_or(LoadErr::IncorrectFormat(format!("'{class}' is not a class")))?,
+        });
+    }
+
+    Ok(records)
+}
+
+/// Put a run onto the leaderboard, keeping it sorted by depth and then score. Returns the place
+/// it got, starting from 0, or None if it wasn't good enough.
+pub fn add_endless_record(records: &mut Vec<EndlessRecord>, new: EndlessRecord) -> Option<usize> {
+    let place = records
+        .iter()
+        .position(|r| (new.depth, new.score) > (r.depth, r.score))
+        .unwrap_or(records.len());
+
+    if place >= ENDLESS_RECORDS {
+        return None;
+    }
+
+    records.insert(place, new);
+    records.truncate(ENDLESS_RECORDS);
+
+    Some(place)
+}
+
+/// Save the endless mode leaderboard to the file.
+pub fn save_endless(records: &[EndlessRecord]) {
+    let save = get_save_path();
+
+    fs::create_dir_all(&save).expect("Couldn't create directories");
+    let mut file = io::BufWriter::new(
+        fs::File::create(save.join(ENDLESS_FILE)).expect("Unable to write save file"),
+    );
+
+    for rec in records {
+        file.write_all(format!("{}:{}:{:X}:{}\n", rec.depth, rec.score, rec.seed, rec.class.tag()).as_bytes())
+            .expect("Unable to write endless record");
+    }
+
+    file.flush().expect("Couldn't write to endless file");
+}
+
+/// Get the best score of each class from the file.
+pub fn load_class_scores() -> Result<HashMap<Class, f64>, LoadErr> {
+    let mut scores = HashMap::new();
+
+    for ln in read_lines(get_save_path().join(CLASS_SCORES_FILE))
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => LoadErr::NotFound,
+            io::ErrorKind::ResourceBusy => {
+                LoadErr::Cant(String::from("the file is already in use"))
+            }
+            e => LoadErr::Other(e),
+        })?
+        .map_while(Result::ok)
+    {
+        let Some((class, score)) = ln.split_once(':') else {
+            return Err(LoadErr::IncorrectFormat(format!("'{ln}' is not class:score")));
+        };
+
+        scores.insert(
+            Class::from_tag(class).ok_or(LoadErr::IncorrectFormat(format!("'{class}' is not a class")))?,
+            score.parse().map_err(|_| LoadErr::IncorrectFormat(format!("'{score}' is not a score")))?,
+        );
+    }
+
+    Ok(scores)
+}
+
+/// Save the best score of each class to the file.
+pub fn save_class_scores(scores: &HashMap<Class, f64>) {
+    let save = get_save_path();
+
+    fs::create_dir_all(&save).expect("Couldn't create directories");
+    let mut file = io::BufWriter::new(
+        fs::File::create(save.join(CLASS_SCORES_FILE)).expect("Unable to write save file"),
+    );
+
+    for (class, score) in scores {
+        file.write_all(format!("{}:{score}\n", class.tag()).as_bytes())
+            .expect("Unable to write class score");
+    }
+
+    file.flush().expect("Couldn't write to class scores file");
+}
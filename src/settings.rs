@@ -0,0 +1,109 @@
+//! Handles the settings that persist between sessions.
+
+use crate::puzzle_loader::{LoadErr, pzl_save::get_save_path, read_lines};
+use std::sync::LazyLock;
+use std::{
+    fs,
+    io::{self, Write},
+};
+
+const SETTINGS_FILE: &str = "settings.txt";
+
+/// Settings loaded from the save directory on first use. A file that can't be read is reported
+/// and replaced by the defaults, so it never stops the game from starting.
+pub static SETTINGS: LazyLock<Settings> = LazyLock::new(|| match load_settings() {
+    Ok(settings) => settings,
+    Err(why) => match why {
+        LoadErr::NotFound => Settings::default(),
+        _ => {
+            eprintln!("Couldn't load {SETTINGS_FILE}, using the default settings instead: {why}");
+            Settings::default()
+        }
+    },
+});
+
+/// Options that can be changed by editing the settings file.
+#[derive(Clone, Debug)]
+pub struct Settings {
+    /// Draw everything using only ascii characters.
+    pub ascii: bool,
+    /// Fewest tiles kept between the player and the edge of the map view before it scrolls.
+    pub camera_margin: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            ascii: false,
+            camera_margin: 5,
+        }
+    }
+}
+
+/// Turn a yes or no into a bool.
+fn parse_flag(key: &str, val: &str) -> Result<bool, LoadErr> {
+    match val {
+        "yes" => Ok(true),
+        "no" => Ok(false),
+        _ => Err(LoadErr::IncorrectFormat(format!(
+            "expected yes or no for '{key}'"
+        ))),
+    }
+}
+
+/// Turn a setting into a number.
+fn parse_num(key: &str, val: &str) -> Result<u32, LoadErr> {
+    val.parse()
+        .map_err(|_| LoadErr::IncorrectFormat(format!("expected a number for '{key}'")))
+}
+
+/// Turn a bool into a yes or no.
+fn flag_str(flag: bool) -> &'static str {
+    if flag { "yes" } else { "no" }
+}
+
+/// Get the settings from the save file. Any setting not in the file keeps its default value.
+pub fn load_settings() -> Result<Settings, LoadErr> {
+    let mut settings = Settings::default();
+
+    for ln in read_lines(get_save_path().join(SETTINGS_FILE))
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => LoadErr::NotFound,
+            io::ErrorKind::ResourceBusy => {
+                LoadErr::Cant(String::from("the file is already in use"))
+            }
+            e => LoadErr::Other(e),
+        })?
+        .map_while(Result::ok)
+    {
+        let Some((key, val)) = ln.split_once(':') else {
+            continue;
+        };
+
+        match key {
+            "ascii" => settings.ascii = parse_flag(key, val)?,
+            "camera_margin" => settings.camera_margin = parse_num(key, val)?,
+            // Ignore settings from other versions.
+            _ => (),
+        }
+    }
+
+    Ok(settings)
+}
+
+/// Save the settings to the file.
+pub fn save_settings(settings: &Settings) {
+    let save = get_save_path();
+
+    fs::create_dir_all(&save).expect("Couldn't create directories");
+    let mut file = io::BufWriter::new(
+        fs::File::create(save.join(SETTINGS_FILE)).expect("Unable to write save file"),
+    );
+
+    file.write_all(format!("ascii:{}\n", flag_str(settings.ascii)).as_bytes())
+        .expect("Unable to write settings");
+    file.write_all(format!("camera_margin:{}\n", settings.camera_margin).as_bytes())
+        .expect("Unable to write settings");
+
+    file.flush().expect("Couldn't write to settings file");
+}
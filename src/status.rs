@@ -0,0 +1,126 @@
+//! Lasting effects on entities that change what they do each turn.
+
+use crossterm::style;
+use std::fmt;
+
+/// Damage done by poison each turn.
+pub const POISON_DMG: u32 = 1;
+/// Turns an entity is frozen for after sliding into something on ice.
+pub const ICE_FREEZE: u32 = 1;
+/// Turns an enemy is poisoned for after being hit with the Venom upgrade.
+pub const VENOM_POISON: u32 = 3;
+/// Turns an enemy is stunned for after being caught in a bomb's explosion.
+pub const BOMB_STUN: u32 = 1;
+/// Turns an enemy is slowed for after being zapped by a wand.
+pub const WAND_SLOW: u32 = 3;
+/// Hits absorbed by the shield every elite starts with.
+pub const ELITE_SHIELD: u32 = 1;
+
+/// The different kinds of status an entity can have.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusKind {
+    /// Takes damage each turn.
+    Poison,
+    /// Can't act.
+    Stun,
+    /// Only acts every other turn.
+    Slow,
+    /// Can't act until it thaws or is hit.
+    Frozen,
+    /// Ignores hits that would do damage.
+    Shield,
+}
+
+impl StatusKind {
+    /// Returns the colour the status is shown in.
+    pub fn clr(self) -> style::Color {
+        match self {
+            StatusKind::Poison => style::Color::Green,
+            StatusKind::Stun => style::Color::Yellow,
+            StatusKind::Slow => style::Color::DarkCyan,
+            StatusKind::Frozen => style::Color::Cyan,
+            StatusKind::Shield => style::Color::Blue,
+        }
+    }
+
+    /// Returns how the status is described in the log, as in "x is poisoned".
+    pub fn adjective(self) -> &'static str {
+        match self {
+            StatusKind::Poison => "poisoned",
+            StatusKind::Stun => "stunned",
+            StatusKind::Slow => "slowed",
+            StatusKind::Frozen => "frozen",
+            StatusKind::Shield => "shielded",
+        }
+    }
+}
+
+impl fmt::Display for StatusKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            StatusKind::Poison => "Poison",
+            StatusKind::Stun => "Stun",
+            StatusKind::Slow => "Slow",
+            StatusKind::Frozen => "Frozen",
+            StatusKind::Shield => "Shield",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A status on an entity. For shields, amount is the number of hits left to absorb, and for
+/// everything else it is the number of turns left.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Status {
+    pub kind: StatusKind,
+    pub amount: u32,
+}
+
+impl Status {
+    /// Create a new status.
+    pub fn new(kind: StatusKind, amount: u32) -> Self {
+        Self { kind, amount }
+    }
+
+    /// Returns true if the status stops the entity acting this turn.
+    pub fn holds(&self) -> bool {
+        match self.kind {
+            StatusKind::Stun | StatusKind::Frozen => true,
+            // Lose every other turn, ending with the last, so a single turn of slow still holds.
+            StatusKind::Slow => self.amount % 2 == 1,
+            StatusKind::Poison | StatusKind::Shield => false,
+        }
+    }
+
+    /// Returns true if the status wears off over time rather than being used up.
+    pub fn timed(&self) -> bool {
+        self.kind != StatusKind::Shield
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.timed() {
+            write!(f, "{} ({} turns)", self.kind, self.amount)
+        } else {
+            write!(f, "{} ({} hits)", self.kind, self.amount)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holds() {
+        assert!(Status::new(StatusKind::Stun, 1).holds());
+        assert!(Status::new(StatusKind::Frozen, 2).holds());
+        assert!(!Status::new(StatusKind::Poison, 1).holds());
+        assert!(!Status::new(StatusKind::Shield, 1).holds());
+
+        // Slow loses every other turn as it wears off, ending with the last.
+        let slowed: Vec<bool> = (1..=4).rev().map(|n| Status::new(StatusKind::Slow, n).holds()).collect();
+        assert_eq!(slowed, [false, true, false, true]);
+    }
+}
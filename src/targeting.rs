@@ -0,0 +1,50 @@
+//! Works out what the player's ranged attacks can hit, for aiming them.
+
+use crate::{Point, fov};
+use crate::attacks::RangedAtk;
+use crate::bn;
+use crate::entity::{En, Special};
+
+/// Returns true if nothing stops movement or a ranged attack passing through this position.
+pub fn clear(map: &bn::Map<En>, pos: Point) -> bool {
+    map.get_map(pos).is_some_and(|t| !t.blocking) && map.get_ent(pos).is_none()
+}
+
+/// Returns true if a ranged attack can be fired from one position to another without anything
+/// in the way. Neither end is checked. Every ranged attack is checked with this, whether it is
+/// fired by the player or an enemy.
+pub fn line_clear(map: &bn::Map<En>, from: Point, to: Point) -> bool {
+    Point::plot_line(from, to)
+        .skip(1)
+        .take_while(|p| *p != to)
+        .all(|p| clear(map, p))
+}
+
+/// Returns the positions of every enemy the attack can hit from the given position, closest
+/// first. Only enemies the player can see, with nothing in the way, are included.
+pub fn targets(map: &bn::Map<En>, pos: Point, atk: &RangedAtk) -> Vec<Point> {
+    let mut targets: Vec<Point> = map
+        .get_entities()
+        .filter(|(p, e)| {
+            !e.is_player
+                && !e.dormant
+                && e.special != Special::WallSentry
+                && fov::visible(**p)
+                && pos.dist_squared(**p) as u32 <= atk.range * atk.range
+                && line_clear(map, pos, **p)
+        })
+        .map(|(p, _)| *p)
+        .collect();
+
+    targets.sort_by_key(|p| pos.dist_squared(*p));
+    targets
+}
+
+/// Returns every tile the attack passes through on the way to and past the target.
+pub fn line_of_fire(map: &bn::Map<En>, pos: Point, target: Point, atk: &RangedAtk) -> Vec<Point> {
+    Point::plot_line(pos, target)
+        .skip(1)
+        .take_while(|p| *p != target)
+        .chain(atk.path(pos, target, |p| map.get_map(p).is_none_or(|t| t.blocking)))
+        .collect()
+}
@@ -1,17 +1,12 @@
 //! Generates templates for enemies and the player that will be used in the map.
 
-use crate::{tile_presets::ARROWS, *};
+use crate::*;
 use attacks::*;
 use entity::*;
 use rand::prelude::IndexedRandom;
 
 pub const PLAYER_CHARACTER: char = '@';
 pub const PLAYER_COLOUR: style::Color = style::Color::Green;
-pub const RING_CHARS: [char; 6] = ['╔', '═', '╗', '║', '╝', '╚'];
-pub const DIAG_CHARS: [char; 4] = ['╱', '╲', '╱', '╲'];
-pub const ELBOWS: [char; 4] = ['└', '┘', '┐', '┌'];
-pub const DIAG_ARROWS: [char; 8] = ['↙', '←', '↖', '↓', '↑', '↘', '→', '↗'];
-pub const WEIRD_ATK_CHAR: char = '☼';
 
 pub mod metadata;
 
@@ -56,12 +51,12 @@ pub fn get_holiness(dmg: u32, duration: usize) -> AtkPat {
     let mut fx: Vec<(_, _)> = places
         .iter()
         .copied()
-        .zip(THICC_FOUR_POS_ATK)
+        .zip(glyphs().thicc_four_pos_atk)
         .map(|(p, ch)| (p, Vfx::new_opaque(ch.yellow(), duration)))
         .collect();
     places.push(Point::new(0, -2));
-    fx.push((Point::new(0, -2), Vfx::new_opaque('║'.yellow(), duration)));
-    fx.push((Point::new(0, 0), Vfx::new_opaque('╬'.yellow(), duration)));
+    fx.push((Point::new(0, -2), Vfx::new_opaque(glyphs().holy_line.yellow(), duration)));
+    fx.push((Point::new(0, 0), Vfx::new_opaque(glyphs().holy_cross.yellow(), duration)));
     let melee = MeleeAtk::new(
         vec![Effect::DoDmg(DmgInst::dmg(dmg, 1.0))],
         places,
@@ -74,6 +69,7 @@ pub fn get_holiness(dmg: u32, duration: usize) -> AtkPat {
 
 /// Generate an attack against the edges of a square.
 pub fn get_ring_attack(dmg: u32, clr: style::Color, edge_dist: i32, duration: usize) -> MeleeAtk {
+    let ring_chars = glyphs().ring;
     let mut atk = MeleeAtk::new(
         vec![Effect::DoDmg(DmgInst::dmg(dmg, 1.0))],
         Vec::new(),
@@ -97,12 +93,12 @@ pub fn get_ring_attack(dmg: u32, clr: style::Color, edge_dist: i32, duration: us
                 0
             };
             let ch = match (right, up) {
-                (1, 0) | (-1, 0) => RING_CHARS[3],
-                (0, 1) | (0, -1) => RING_CHARS[1],
-                (1, 1) => RING_CHARS[2],
-                (-1, 1) => RING_CHARS[0],
-                (1, -1) => RING_CHARS[4],
-                (-1, -1) => RING_CHARS[5],
+                (1, 0) | (-1, 0) => ring_chars[3],
+                (0, 1) | (0, -1) => ring_chars[1],
+                (1, 1) => ring_chars[2],
+                (-1, 1) => ring_chars[0],
+                (1, -1) => ring_chars[4],
+                (-1, -1) => ring_chars[5],
                 _ => continue,
             };
             let p = Point::new(x, y);
@@ -125,7 +121,7 @@ pub fn get_diag_atks(
     let mut atk_pat = AtkPat::empty();
     let mut dir = Point::new(1, 1);
 
-    for &ch in &DIAG_CHARS {
+    for ch in glyphs().diag {
         let mut place = Vec::new();
         let mut fx = Vec::new();
 
@@ -149,20 +145,110 @@ pub fn get_diag_atks(
     atk_pat
 }
 
-/// Creates the player entity.
-pub fn get_player() -> En {
-    En::new(
-        9,
-        true,
+/// Returns the character that best shows a line going in the given direction.
+fn line_char(disp: Point) -> char {
+    if disp.x.abs() >= disp.y.abs() * 2 {
+        FOUR_POS_ATK[0]
+    } else if disp.y.abs() >= disp.x.abs() * 2 {
+        FOUR_POS_ATK[1]
+    } else if disp.x * disp.y > 0 {
+        glyphs().diag[0]
+    } else {
+        glyphs().diag[1]
+    }
+}
+
+/// Shows an arrow flying along the line, which ends at the target.
+fn arrow_fx(hit: bool, line: Vec<Point>) -> Vec<(Point, Vfx)> {
+    let Some(&target) = line.last() else {
+        return Vec::new();
+    };
+    let ch = line_char(target - line[0]);
+
+    let mut fx: Vec<(Point, Vfx)> = line[..line.len() - 1]
+        .iter()
+        .map(|p| (*p, Vfx::opaque_with_clr(ch, style::Color::Yellow, 5)))
+        .collect();
+    fx.push((target, if hit {
+        Vfx::opaque_with_clr('x', style::Color::Red, 7)
+    } else {
+        Vfx::opaque_with_clr('?', style::Color::White, 7)
+    }));
+    fx
+}
+
+/// Shows a bolt of magic at the target.
+fn bolt_fx(hit: bool, line: Vec<Point>) -> Vec<(Point, Vfx)> {
+    let Some(&target) = line.last() else {
+        return Vec::new();
+    };
+
+    let mut fx: Vec<(Point, Vfx)> = line[..line.len() - 1]
+        .iter()
+        .map(|p| (*p, Vfx::opaque_with_clr('*', style::Color::Magenta, 5)))
+        .collect();
+    fx.push((target, if hit {
+        Vfx::new_opaque(' '.on_magenta(), 7)
+    } else {
+        Vfx::opaque_with_clr('?', style::Color::White, 7)
+    }));
+    fx
+}
+
+/// Shows a laser along the whole line, like the one the boss fires.
+fn laser_fx(_hit: bool, line: Vec<Point>) -> Vec<(Point, Vfx)> {
+    let Some(&end) = line.last() else {
+        return Vec::new();
+    };
+    let ch = line_char(end - line[0]);
+
+    line.into_iter()
+        .map(|p| (p, Vfx::opaque_with_clr(ch, style::Color::Red, 10)))
+        .collect()
+}
+
+/// Creates a bow that shoots a single enemy, but sometimes misses.
+pub fn get_bow(dmg: u32, range: u32) -> RangedAtk {
+    RangedAtk::new(
+        "Bow",
+        vec![Effect::DoDmg(DmgInst::dmg(dmg, 0.9))],
+        range,
         Vec::new(),
-        PLAYER_CHARACTER.with(PLAYER_COLOUR),
-        Special::Not,
-        Point::ORIGIN.get_all_adjacent_diagonal(),
-        get_default_atks(1, FOUR_POS_ATK, style::Color::Red),
-        false,
+        Box::new(arrow_fx),
     )
 }
 
+/// Creates a wand that does a lot of damage to a single enemy, but not very far away.
+pub fn get_wand(dmg: u32, range: u32) -> RangedAtk {
+    RangedAtk::new(
+        "Wand",
+        vec![
+            Effect::DoDmg(DmgInst::dmg(dmg, 1.0)),
+            Effect::Inflict(status::Status::new(status::StatusKind::Slow, status::WAND_SLOW)),
+        ],
+        range,
+        Vec::new(),
+        Box::new(bolt_fx),
+    )
+}
+
+/// Creates a laser that hits everything in a line until it reaches a wall.
+pub fn get_laser(dmg: u32, range: u32) -> RangedAtk {
+    RangedAtk::new(
+        "Laser",
+        vec![Effect::DoDmg(DmgInst::dmg(dmg, 1.0).of_kind(DmgKind::Laser))],
+        range,
+        Vec::new(),
+        Box::new(laser_fx),
+    )
+    .piercing()
+}
+
+/// Creates the player entity as a fighter, which is how puzzles are played.
+pub fn get_player() -> En {
+    En::from_template(&classes::Class::Fighter.template(), true, false)
+}
+
 /// Create an explosion with the given manhattan radius.
 pub fn get_explosion(dmg: u32, radius: i32, ch: StyleCh) -> MeleeAtk {
     let mut positions = Vec::new();
@@ -187,7 +273,8 @@ pub fn get_explosion(dmg: u32, radius: i32, ch: StyleCh) -> MeleeAtk {
 
     MeleeAtk::new(
         vec![
-            Effect::DoDmg(DmgInst::dmg(dmg, 1.0)),
+            Effect::DoDmg(DmgInst::dmg(dmg, 1.0).of_kind(DmgKind::Explosive)),
+            Effect::Other(Box::new(|_, to, map| tile_presets::crumble(map, to))),
         ],
         positions,
         fx,
@@ -208,12 +295,13 @@ pub fn get_missile(dir: Point, clr: style::Color, explosion: MeleeAtk) -> En {
                 Box::new(ActionType::ForceMelee(Point::ORIGIN, 0)),
             ),
         ],
-        ARROWS[dir.dir()].with(clr),
+        glyphs().arrows[dir.dir()].with(clr),
         Special::Missile,
         Vec::new(),
         atk_pat,
         false,
     )
+    .with_resist(Resistances::NONE.with(DmgKind::Laser, Resist::Immune))
 }
 
 /// Return an effect that pushes the target and damages them if they collide with something.
@@ -234,9 +322,9 @@ pub fn get_push_effect(collide_dmg: u32, push_strength: i32) -> Effect {
             if let Some(ch) = collide {
                 return vec![bn::Cmd::new_on(to).modify_entity(Box::new(move |e: &mut En| {
                     let old = *e.hp;
-                    e.apply_dmg(DmgInst::dmg(collide_dmg, 1.0));
+                    e.apply_dmg(DmgInst::dmg(collide_dmg, 1.0).of_kind(DmgKind::Collision));
                     let mut write = LOG_MSGS.write().unwrap();
-                    write.push(LogMsg::new(format!("{} collides with {ch}", e.ch.content())));
+                    write.push(LogMsg::new(LogEvent::Collision { who: *e.ch.content(), with: ch }));
                     write.push(
                         LogMsg::hp_change(old, &e.hp, *e.ch.content())
                     );
@@ -248,18 +336,16 @@ pub fn get_push_effect(collide_dmg: u32, push_strength: i32) -> Effect {
                 PLAYER = new;
             }
         }
-        vec![
-            bn::Cmd::new_on(to).modify_entity(Box::new(move |e: &mut En| {
-                e.vel = Some(disp * -push_strength);
-            })),
-            bn::Cmd::new_on(to).move_to(new)
-        ]
+        let mut cmds = vec![bn::Cmd::new_on(to).modify_entity(Box::new(move |e: &mut En| {
+            e.vel = Some(disp * -push_strength);
+        }))];
+        cmds.extend(tile_presets::force_move(map, to, new));
+        cmds
     }))
 }
 
-/// Return a vector of entity templates for use in the game. First vector is for normal enemies,
-/// second is for elite enemies.
-pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
+/// Returns every move a chess knight can make.
+pub fn get_knight_moves() -> Vec<Point> {
     // Generate knight moves without typing them all out.
     let mut p1 = Point::new(2, 1);
     let mut p2 = Point::new(2, -1);
@@ -272,14 +358,61 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
         p1.rotate_90_cw_ip();
         p2.rotate_90_cw_ip();
     }
+    knight
+}
+
+/// Returns moves exactly n tiles in a straight line using manhattan movement.
+pub fn get_manhattan_n(n: i32) -> Vec<Point> {
+    Point::ORIGIN
+        .get_all_adjacent()
+        .iter()
+        .map(|&p| p * n)
+        .collect()
+}
+
+/// Creates attacks that hit a knight move away, done by moving in that direction.
+pub fn get_knight_atks() -> AtkPat {
+    let mut knight_attacks = AtkPat::empty();
+    for p in get_knight_moves() {
+        let mut fx = Vec::new();
+        let (init_dir, last_dir) = if p.x.abs() > p.y.abs() {
+            (Point::new(p.x, 0), Point::new(0, p.y))
+        } else {
+            (Point::new(0, p.y), Point::new(p.x, 0))
+        };
+        let half_init = init_dir / 2;
+        fx.push((half_init, Vfx::new_opaque(FOUR_POS_ATK[last_dir.dir()].red(), 7)));
+        fx.push((p, Vfx::new_opaque(FOUR_POS_ATK[half_init.dir()].red(), 7)));
+        let mut elb_idx = match half_init + last_dir {
+            Point { x: 1, y: 1 } => 1,
+            Point { x: -1, y: 1 } => 0,
+            Point { x: 1, y: -1 } => 2,
+            Point { x: -1, y: -1 } => 3,
+            _ => unreachable!(),
+        };
+        if half_init.y != 0 {
+            elb_idx += 2;
+            if elb_idx > 3 {
+                elb_idx -= 4;
+            }
+        }
+        fx.push((init_dir, Vfx::new_opaque(glyphs().elbows[elb_idx].red(), 7)));
 
-    fn get_manhattan_n(n: i32) -> Vec<Point> {
-        Point::ORIGIN
-            .get_all_adjacent()
-            .iter()
-            .map(|&p| p * n)
-            .collect()
+        knight_attacks.melee_atks
+            .insert(p, vec![MeleeAtk::new(
+                vec![Effect::DoDmg(DmgInst::dmg(1, 1.0))],
+                vec![p],
+                fx,
+                Vfx::new_opaque('?'.stylize(), 8)
+            )]);
     }
+    knight_attacks
+}
+
+/// Return a vector of entity templates for use in the game. First vector is for normal enemies,
+/// second is for elite enemies.
+pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
+    let knight = get_knight_moves();
 
     // Manhattan movement.
     let manhattan = Point::ORIGIN.get_all_adjacent();
@@ -315,40 +448,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
     let default_atks = get_default_atks(1, FOUR_POS_ATK, style::Color::Red);
 
     // Attacks from a knight move away.
-    let mut knight_attacks = AtkPat::empty();
-    for &p in knight.iter() {
-        let mut fx = Vec::new();
-        let (init_dir, last_dir) = if p.x.abs() > p.y.abs() {
-            (Point::new(p.x, 0), Point::new(0, p.y))
-        } else {
-            (Point::new(0, p.y), Point::new(p.x, 0))
-        };
-        let half_init = init_dir / 2;
-        fx.push((half_init, Vfx::new_opaque(FOUR_POS_ATK[last_dir.dir()].red(), 7)));
-        fx.push((p, Vfx::new_opaque(FOUR_POS_ATK[half_init.dir()].red(), 7)));
-        let mut elb_idx = match half_init + last_dir {
-            Point { x: 1, y: 1 } => 1,
-            Point { x: -1, y: 1 } => 0,
-            Point { x: 1, y: -1 } => 2,
-            Point { x: -1, y: -1 } => 3,
-            _ => unreachable!(),
-        };
-        if half_init.y != 0 {
-            elb_idx += 2;
-            if elb_idx > 3 {
-                elb_idx -= 4;
-            }
-        }
-        fx.push((init_dir, Vfx::new_opaque(ELBOWS[elb_idx].red(), 7)));
-
-        knight_attacks.melee_atks
-            .insert(p, vec![MeleeAtk::new(
-                vec![Effect::DoDmg(DmgInst::dmg(1, 1.0))],
-                vec![p],
-                fx,
-                Vfx::new_opaque('?'.stylize(), 8)
-            )]);
-    }
+    let knight_attacks = get_knight_atks();
 
     // Attack pattern for the i.
     let mut i_atks = default_atks.clone();
@@ -446,10 +546,10 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
     );
 
     // Functionally identical to default attacks, but looks different.
-    let weird_default = get_default_atks(1, [WEIRD_ATK_CHAR; 4], style::Color::Magenta);
+    let weird_default = get_default_atks(1, [glyphs().weird_atk; 4], style::Color::Magenta);
 
     // Default attack pattern with double damage and knockback.
-    let heavy_default_atks = get_hvy_atks(2, THICC_FOUR_POS_ATK, style::Color::Red);
+    let heavy_default_atks = get_hvy_atks(2, glyphs().thicc_four_pos_atk, style::Color::Red);
 
     // Suicide bomb.
     let mut bomb = AtkPat::empty();
@@ -461,7 +561,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
         style::Color::Red,
         7,
         Vfx::new_opaque('?'.stylize(), 7),
-        EIGHT_POS_ATK,
+        glyphs().eight_pos_atk,
     ));
 
     // Like diagonal_atks, but without the default_atks in it.
@@ -493,7 +593,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                     DAMAGE_DEALT += *e.hp;
                 }
             }
-            vec![bn::Cmd::new_on(to).move_to(new)]
+            tile_presets::force_move(map, to, new)
         }))],
         style::Color::Magenta,
         7,
@@ -521,6 +621,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                     PLAYER = from;
                 }
             }
+            // Both tiles are still stood on afterwards, so no plate is let go of.
             vec![
                 bn::Cmd::new_on(to).move_to(from),
                 bn::Cmd::new_on(to).create_entity(slf)
@@ -529,7 +630,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
         style::Color::Yellow,
         7,
         Vfx::new_opaque('?'.stylize(), 7),
-        EIGHT_POS_ATK
+        glyphs().eight_pos_atk
     ));
 
     for (_d, atks) in swap.melee_atks.iter_mut() {
@@ -548,7 +649,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
         style::Color::Yellow,
         7,
         Vfx::new_opaque('?'.stylize(), 7),
-        DIAG_ARROWS,
+        glyphs().diag_arrows,
     ));
 
     // Like wizardry, but with a weird default attack included.
@@ -575,7 +676,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
             .push(get_ring_attack(2, style::Color::Red, edge_dist, 10));
     }
 
-    fn _go_furthest(map: &bn::Map<En>, _en: &En, _pos: Point) -> Vec<bn::Cmd<En>> {
+    fn _go_furthest(map: &bn::Map<En>, _en: &En, pos: Point) -> Vec<bn::Cmd<En>> {
         let pl = unsafe { PLAYER };
         let mut max_dist = 0;
         let mut new_pos = Point::ORIGIN;
@@ -598,10 +699,10 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
             }
         }
 
-        vec![bn::Cmd::new_here().move_to(new_pos)]
+        tile_presets::force_move(map, pos, new_pos)
     }
 
-    fn pl_to_wall(map: &bn::Map<En>, dir: Point) -> Vec<bn::Cmd<En>> {
+    fn pl_to_wall(map: &bn::Map<En>, pos: Point, dir: Point) -> Vec<bn::Cmd<En>> {
         let mut cur = unsafe { PLAYER };
         while !map.get_map(cur).unwrap().blocking {
             cur = cur + dir;
@@ -612,7 +713,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
             }
         }
 
-        vec![bn::Cmd::new_here().move_to(cur)]
+        tile_presets::force_move(map, pos, cur)
     }
 
     fn fire_laser(
@@ -634,7 +735,23 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 } else {
                     cmds.push(
                         bn::Cmd::new_on(from).modify_entity(Box::new(move |e: &mut En| {
-                            e.apply_dmg(DmgInst::dmg(dmg, 1.0));
+                            let inst = DmgInst::dmg(dmg, 1.0).of_kind(DmgKind::Laser);
+                            let old = *e.hp;
+                            let amount = e.dmg_taken(inst);
+                            e.apply_dmg(inst);
+
+                            let mut handle = LOG_MSGS.write().unwrap();
+                            let to = *e.ch.content();
+                            handle.push(
+                                LogEvent::Damage {
+                                    from: glyphs().boss,
+                                    to,
+                                    amount: amount as u32,
+                                    kind: DmgKind::Laser,
+                                }
+                                .into(),
+                            );
+                            handle.push(LogMsg::hp_change(old, &e.hp, to));
                         })),
                     );
                 }
@@ -655,10 +772,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 possible.push(new);
             }
         }
-        vec![
-            bn::Cmd::new_on(pos)
-                .move_to(*possible.choose(&mut rand::rng()).unwrap()),
-        ]
+        tile_presets::force_move(map, pos, *possible.choose(&mut rand::rng()).unwrap())
     }
     
     fn triple_missile(
@@ -702,6 +816,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
         movement: manhattan2.clone(),
         ch: 'p'.stylize(),
         atks: diagonal_atks.clone(),
+        resist: Resistances::NONE,
     };
 
     (
@@ -718,6 +833,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: manhattan.clone(),
                 ch: 'e'.stylize(),
                 atks: default_atks.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 3,
@@ -736,6 +852,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: long_manhattan.clone(),
                 ch: 'f'.stylize(),
                 atks: diagonal_atks.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 4,
@@ -750,6 +867,8 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: manhattan.clone(),
                 ch: 'h'.stylize(),
                 atks: heavy_default_atks.clone(),
+                // Too well armoured to be hurt by running into things.
+                resist: Resistances::NONE.with(DmgKind::Collision, Resist::Immune),
             },
             EntityTemplate {
                 max_hp: 2,
@@ -760,6 +879,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: manhattan.clone(),
                 ch: 'l'.stylize(),
                 atks: spear.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 2,
@@ -773,6 +893,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: knight.clone(),
                 ch: 'k'.stylize(),
                 atks: diagonal_atks.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 2,
@@ -786,6 +907,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: knight.clone(),
                 ch: 'n'.stylize(),
                 atks: knight_attacks.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 3,
@@ -796,6 +918,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: diag.clone(),
                 ch: 'b'.stylize(),
                 atks: pure_diag_atks.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 3,
@@ -810,6 +933,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: manhattan2.clone(),
                 ch: 'r'.stylize(),
                 atks: default_atks.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 3,
@@ -823,6 +947,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: manhattan.clone(),
                 ch: 'w'.stylize(),
                 atks: wizardry_plus.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 2,
@@ -835,6 +960,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: manhattan.clone(),
                 ch: 'o'.stylize(),
                 atks: default_atks.clone(),
+                resist: Resistances::NONE,
             },
             get_minion(),
             p_temp.clone(),
@@ -853,6 +979,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: vec![Point::new(0, 1), Point::new(0, -1)],
                 ch: 'i'.stylize(),
                 atks: i_atks.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 1,
@@ -865,6 +992,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: manhattan.clone(),
                 ch: 'c'.stylize(),
                 atks: AtkPat::empty(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 2,
@@ -879,6 +1007,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: viking_move.clone(),
                 ch: 'v'.stylize(),
                 atks: viking_atk.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 1,
@@ -891,6 +1020,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: manhattan23.clone(),
                 ch: 'd'.stylize(),
                 atks: small_ring_fast.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 2,
@@ -908,6 +1038,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: fair_total.clone(),
                 ch: 'g'.stylize(),
                 atks: diagonal_atks.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 2,
@@ -925,6 +1056,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: fair_total.clone(),
                 ch: 't'.stylize(),
                 atks: swap.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 1,
@@ -942,6 +1074,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: queen_move.clone(),
                 ch: 'q'.stylize(),
                 atks: spear.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 2,
@@ -956,6 +1089,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: fair_total.clone(),
                 ch: 'j'.stylize(),
                 atks: spear.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 1,
@@ -969,6 +1103,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: diag.clone(),
                 ch: 'y'.stylize(),
                 atks: diagonal_atks.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 2,
@@ -979,6 +1114,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: long_manhattan.clone(),
                 ch: 'x'.stylize(),
                 atks: reverse_wizardry.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 1,
@@ -992,6 +1128,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: manhattan.clone(),
                 ch: 's'.stylize(),
                 atks: AtkPat::empty(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 1,
@@ -1006,6 +1143,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: manhattan.clone(),
                 ch: 'm'.stylize(),
                 atks: AtkPat::empty(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 1,
@@ -1020,6 +1158,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: manhattan2.clone(),
                 ch: 'u'.stylize(),
                 atks: bomb.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 2,
@@ -1033,6 +1172,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: knight.clone(),
                 ch: 'z'.stylize(),
                 atks: get_holiness(2, 10).clone(),
+                resist: Resistances::NONE,
             },
         ],
         // Capitals start here.
@@ -1051,6 +1191,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: diag_plus.clone(),
                 ch: 'B'.stylize(),
                 atks: get_holiness(3, 15),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 4,
@@ -1064,6 +1205,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: manhattan.clone(),
                 ch: 'L'.stylize(),
                 atks: long_spear.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 3,
@@ -1085,6 +1227,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: knight.clone(),
                 ch: 'K'.stylize(),
                 atks: diagonal_atks.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 2,
@@ -1095,6 +1238,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: queen_move.clone(),
                 ch: 'Q'.stylize(),
                 atks: queen_attack.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 5,
@@ -1120,6 +1264,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: manhattan.clone(),
                 ch: 'E'.stylize(),
                 atks: default_atks.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 2,
@@ -1141,6 +1286,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: viking_move.clone(),
                 ch: 'V'.stylize(),
                 atks: viking_atk.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 4,
@@ -1158,6 +1304,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: manhattan23.clone(),
                 ch: 'R'.stylize(),
                 atks: spear.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 3,
@@ -1175,6 +1322,7 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                 movement: ring.clone(),
                 ch: 'O'.stylize(),
                 atks: ring_atk.clone(),
+                resist: Resistances::NONE,
             },
             EntityTemplate {
                 max_hp: 6,
@@ -1183,17 +1331,17 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                     ActionType::ForceMelee(Point::ORIGIN, 0),
                     ActionType::ForceMelee(Point::ORIGIN, 1),
                     ActionType::ForceMelee(Point::ORIGIN, 2),
-                    ActionType::Arbitrary(Box::new(|map, _en, _pos| {
-                        pl_to_wall(map, Point::new(1, 0))
+                    ActionType::Arbitrary(Box::new(|map, _en, pos| {
+                        pl_to_wall(map, pos, Point::new(1, 0))
                     })),
                     ActionType::Multi(
                         Box::new(ActionType::Arbitrary(Box::new(|map, _en, pos| {
                             fire_laser(pos, map, 3, '-'.red(), Point::new(-1, 0))
                         }))),
                         Box::new(ActionType::Arbitrary(Box::new(|map, _en, pos| {
-                            let mut cmds = pl_to_wall(map, Point::new(0, 1));
+                            let mut cmds = pl_to_wall(map, pos, Point::new(0, 1));
                             cmds.push(
-                                bn::Cmd::new_on(pos).create_effect(Vfx::new_opaque('Ω'.white(), 9)),
+                                bn::Cmd::new_on(pos).create_effect(Vfx::new_opaque(glyphs().boss.white(), 9)),
                             );
                             cmds
                         }))),
@@ -1203,9 +1351,9 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                             fire_laser(pos, map, 3, '|'.red(), Point::new(0, -1))
                         }))),
                         Box::new(ActionType::Arbitrary(Box::new(|map, _en, pos| {
-                            let mut cmds = pl_to_wall(map, Point::new(-1, 0));
+                            let mut cmds = pl_to_wall(map, pos, Point::new(-1, 0));
                             cmds.push(
-                                bn::Cmd::new_on(pos).create_effect(Vfx::new_opaque('Ω'.white(), 9)),
+                                bn::Cmd::new_on(pos).create_effect(Vfx::new_opaque(glyphs().boss.white(), 9)),
                             );
                             cmds
                         }))),
@@ -1215,9 +1363,9 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                             fire_laser(pos, map, 3, '-'.red(), Point::new(1, 0))
                         }))),
                         Box::new(ActionType::Arbitrary(Box::new(|map, _en, pos| {
-                            let mut cmds = pl_to_wall(map, Point::new(0, -1));
+                            let mut cmds = pl_to_wall(map, pos, Point::new(0, -1));
                             cmds.push(
-                                bn::Cmd::new_on(pos).create_effect(Vfx::new_opaque('Ω'.white(), 9)),
+                                bn::Cmd::new_on(pos).create_effect(Vfx::new_opaque(glyphs().boss.white(), 9)),
                             );
                             cmds
                         }))),
@@ -1227,9 +1375,9 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                     })),
                     ActionType::Wait,
                     ActionType::Arbitrary(Box::new(|map, _en, pos| {
-                        let mut cmds = pl_to_wall(map, Point::new(-1, 0));
+                        let mut cmds = pl_to_wall(map, pos, Point::new(-1, 0));
                         cmds.push(
-                            bn::Cmd::new_on(pos).create_effect(Vfx::new_opaque('Ω'.white(), 9)),
+                            bn::Cmd::new_on(pos).create_effect(Vfx::new_opaque(glyphs().boss.white(), 9)),
                         );
                         cmds
                     })),
@@ -1238,9 +1386,9 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                         triple_missile(pos, 2, disp)
                     })),
                     ActionType::Arbitrary(Box::new(|map, _en, pos| {
-                        let mut cmds = pl_to_wall(map, Point::new(0, 1));
+                        let mut cmds = pl_to_wall(map, pos, Point::new(0, 1));
                         cmds.push(
-                            bn::Cmd::new_on(pos).create_effect(Vfx::new_opaque('Ω'.white(), 9)),
+                            bn::Cmd::new_on(pos).create_effect(Vfx::new_opaque(glyphs().boss.white(), 9)),
                         );
                         cmds
                     })),
@@ -1249,9 +1397,9 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                         triple_missile(pos, 2, disp)
                     })),
                     ActionType::Arbitrary(Box::new(|map, _en, pos| {
-                        let mut cmds = pl_to_wall(map, Point::new(1, 0));
+                        let mut cmds = pl_to_wall(map, pos, Point::new(1, 0));
                         cmds.push(
-                            bn::Cmd::new_on(pos).create_effect(Vfx::new_opaque('Ω'.white(), 9)),
+                            bn::Cmd::new_on(pos).create_effect(Vfx::new_opaque(glyphs().boss.white(), 9)),
                         );
                         cmds
                     })),
@@ -1260,9 +1408,9 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                         triple_missile(pos, 2, disp)
                     })),
                     ActionType::Arbitrary(Box::new(|map, _en, pos| {
-                        let mut cmds = pl_to_wall(map, Point::new(0, -1));
+                        let mut cmds = pl_to_wall(map, pos, Point::new(0, -1));
                         cmds.push(
-                            bn::Cmd::new_on(pos).create_effect(Vfx::new_opaque('Ω'.white(), 9)),
+                            bn::Cmd::new_on(pos).create_effect(Vfx::new_opaque(glyphs().boss.white(), 9)),
                         );
                         cmds
                     })),
@@ -1271,9 +1419,9 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                         triple_missile(pos, 2, disp)
                     })),
                     ActionType::Arbitrary(Box::new(|map, _en, pos| {
-                        let mut cmds = pl_to_wall(map, Point::new(-1, 0));
+                        let mut cmds = pl_to_wall(map, pos, Point::new(-1, 0));
                         cmds.push(
-                            bn::Cmd::new_on(pos).create_effect(Vfx::new_opaque('Ω'.white(), 9)),
+                            bn::Cmd::new_on(pos).create_effect(Vfx::new_opaque(glyphs().boss.white(), 9)),
                         );
                         cmds
                     })),
@@ -1288,8 +1436,9 @@ pub fn get_templates() -> (Vec<EntityTemplate>, Vec<EntityTemplate>) {
                     ActionType::Wait,
                 ],
                 movement: ring.clone(),
-                ch: 'Ω'.stylize(),
+                ch: glyphs().boss.stylize(),
                 atks: omegattacks.clone(),
+                resist: Resistances::NONE,
             },
         ],
     )
@@ -1308,6 +1457,7 @@ pub fn get_minion() -> EntityTemplate {
         ],
         movement: Rect::new(-1, 1, 3, 3).edges().collect(),
         ch: 'a'.stylize(),
-        atks: get_default_atks(1, FOUR_POS_ATK, style::Color::Red)
+        atks: get_default_atks(1, FOUR_POS_ATK, style::Color::Red),
+        resist: Resistances::NONE,
     }
 }
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use crate::{get_assets_path, puzzle_loader::read_lines};
+use crate::{get_assets_path, glyphs, puzzle_loader::read_lines};
 
 /// Name of the file containing the description of each enemy.
 pub const DESC_FILE: &str = "desc.txt";
@@ -315,7 +315,7 @@ pub fn get_metadata() -> HashMap<char, TempMeta> {
             },
         ),
         (
-            'Ω',
+            glyphs().boss,
             TempMeta {
                 cost: 100,
                 floor_rang: 5..=5,
@@ -0,0 +1,188 @@
+//! Themes that change how each floor looks and what is found on it.
+
+use crate::map_gen::bandit_gen::LAST_NORMAL_FLOOR;
+use crate::map_gen::shapes::{DEFAULT_MIX, Shape};
+use crossterm::style;
+use rand::Rng;
+use rand::prelude::IndexedRandom;
+use std::fmt;
+
+/// Theme of the floor the player is on.
+pub static mut THEME: Theme = Theme::Halls;
+
+/// How likely an ordinary room is to get each kind of feature.
+#[derive(Clone, Copy, Debug)]
+pub struct Features {
+    /// Chance of a few spike traps.
+    pub spikes: f64,
+    /// Chance of a pair of teleporters.
+    pub teleporters: f64,
+    /// Chance of a conveyor belt.
+    pub conveyors: f64,
+    /// Chance of a cracked wall.
+    pub cracked: f64,
+}
+
+impl Features {
+    /// Chances on a floor without anything special about it.
+    pub const NORMAL: Self = Self {
+        spikes: 0.25,
+        teleporters: 0.15,
+        conveyors: 0.15,
+        cracked: 0.2,
+    };
+}
+
+/// The themes a floor can have.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Theme {
+    /// Nothing out of the ordinary.
+    #[default]
+    Halls,
+    /// Ice and caverns everywhere.
+    Glacier,
+    /// Conveyors, teleporters and pillars.
+    Factory,
+    /// Full of traps and crumbling walls.
+    Crypt,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 4] = [Theme::Halls, Theme::Glacier, Theme::Factory, Theme::Crypt];
+
+    /// Pick the theme of a floor. The floors after the normal ones are always halls.
+    pub fn pick<R: Rng>(rng: &mut R, floor_num: u32) -> Self {
+        if floor_num > LAST_NORMAL_FLOOR {
+            return Theme::Halls;
+        }
+        *Self::ALL
+            .choose_weighted(rng, |t| t.weight())
+            .unwrap_or(&Theme::Halls)
+    }
+
+    /// Returns how often the theme is picked compared to the others.
+    fn weight(self) -> u32 {
+        match self {
+            Theme::Halls => 3,
+            Theme::Glacier | Theme::Factory | Theme::Crypt => 1,
+        }
+    }
+
+    /// Returns the colour of walls and floors, or None to use the usual colour of the floor.
+    pub fn wall_clr(self) -> Option<style::Color> {
+        match self {
+            Theme::Halls => None,
+            Theme::Glacier => Some(style::Color::Rgb { r: 140, g: 190, b: 220 }),
+            Theme::Factory => Some(style::Color::Rgb { r: 150, g: 110, b: 60 }),
+            Theme::Crypt => Some(style::Color::Rgb { r: 110, g: 90, b: 130 }),
+        }
+    }
+
+    /// Returns the colour of doors, or None to use the usual colour of the floor.
+    pub fn door_clr(self) -> Option<style::Color> {
+        match self {
+            Theme::Halls => None,
+            Theme::Glacier => Some(style::Color::Rgb { r: 210, g: 240, b: 255 }),
+            Theme::Factory => Some(style::Color::Yellow),
+            Theme::Crypt => Some(style::Color::Rgb { r: 190, g: 70, b: 190 }),
+        }
+    }
+
+    /// Returns how often each room shape is used.
+    pub fn shapes(self) -> &'static [(Shape, u32)] {
+        match self {
+            Theme::Halls => &DEFAULT_MIX,
+            Theme::Glacier => &[
+                (Shape::Plain, 6),
+                (Shape::Cavern, 4),
+                (Shape::L, 1),
+                (Shape::Cross, 1),
+            ],
+            Theme::Factory => &[
+                (Shape::Plain, 4),
+                (Shape::Pillars, 6),
+                (Shape::Cross, 3),
+                (Shape::T, 3),
+                (Shape::L, 2),
+            ],
+            Theme::Crypt => &[
+                (Shape::Plain, 3),
+                (Shape::Cavern, 6),
+                (Shape::Cross, 3),
+                (Shape::L, 2),
+                (Shape::T, 2),
+                (Shape::Pillars, 2),
+            ],
+        }
+    }
+
+    /// Returns the chance of each room becoming an ice puzzle.
+    pub fn ice(self) -> f64 {
+        match self {
+            Theme::Halls => 0.15,
+            Theme::Glacier => 0.5,
+            Theme::Factory | Theme::Crypt => 0.05,
+        }
+    }
+
+    /// Returns how likely rooms are to have each kind of feature.
+    pub fn features(self) -> Features {
+        match self {
+            Theme::Halls => Features::NORMAL,
+            Theme::Glacier => Features {
+                spikes: 0.1,
+                teleporters: 0.1,
+                conveyors: 0.0,
+                cracked: 0.2,
+            },
+            Theme::Factory => Features {
+                spikes: 0.15,
+                teleporters: 0.35,
+                conveyors: 0.6,
+                cracked: 0.1,
+            },
+            Theme::Crypt => Features {
+                spikes: 0.7,
+                teleporters: 0.1,
+                conveyors: 0.0,
+                cracked: 0.5,
+            },
+        }
+    }
+
+    /// Returns how likely the enemy is to be picked compared to the others that can be found on
+    /// the floor.
+    pub fn enemy_weight(self, ch: char) -> u32 {
+        match (self, ch) {
+            (Theme::Glacier, 'r' | 'l') => 3,
+            (Theme::Glacier, 'o') => 2,
+            (Theme::Factory, 'x') => 3,
+            (Theme::Factory, 'k' | 'n') => 2,
+            (Theme::Crypt, 's' | 'i') => 3,
+            (Theme::Crypt, 'v' | 'h') => 2,
+            _ => 1,
+        }
+    }
+
+    /// Returns the colour the theme is announced in.
+    pub fn clr(self) -> style::Color {
+        self.door_clr().unwrap_or(style::Color::White)
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Theme::Halls => "Halls",
+            Theme::Glacier => "Glacier",
+            Theme::Factory => "Factory",
+            Theme::Crypt => "Crypt",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Returns the theme of the floor the player is on.
+pub fn current() -> Theme {
+    unsafe { THEME }
+}
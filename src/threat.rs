@@ -0,0 +1,249 @@
+//! Works out which tiles enemies can hit on their next turn, for the threat overlay.
+
+use crate::{ActionType, Point, accuracy, fov};
+use crate::targeting::{clear, line_clear};
+use crate::attacks::{AtkPat, Effect};
+use crate::bn;
+use crate::entity::{En, PLAYER, Special};
+use crossterm::style::{self, Stylize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// Whether or not the threat overlay is shown.
+pub static SHOW_THREATS: RwLock<bool> = RwLock::new(false);
+
+/// How many actions deep to look through actions that contain other actions.
+const MAX_DEPTH: usize = 4;
+/// Most tiles a path is followed for.
+const MAX_PATH: usize = 50;
+/// Background of tiles that can be damaged.
+const DMG_CLR: style::Color = style::Color::DarkRed;
+/// Background of tiles that something will pass through without doing damage.
+const PATH_CLR: style::Color = style::Color::DarkYellow;
+/// Colour of the damage shown on tiles that might not be hit.
+const UNSURE_CLR: style::Color = style::Color::Yellow;
+
+type StyleCh = style::StyledContent<char>;
+
+/// Returns the damage done by a list of effects.
+fn effects_dmg(effects: &[Effect]) -> i32 {
+    effects
+        .iter()
+        .map(|ef| match ef {
+            Effect::DoDmg(inst) => inst.total_dmg(),
+            Effect::Other(_) | Effect::Inflict(_) => 0,
+        })
+        .sum()
+}
+
+/// Stores the damage that could be done to each tile on the next turn. A value of None means
+/// something will pass through the tile without damaging it.
+#[derive(Clone, Debug, Default)]
+pub struct ThreatMap {
+    pub tiles: HashMap<Point, Option<i32>>,
+    /// Lowest chance of any attack on each damaged tile hitting.
+    pub chances: HashMap<Point, f64>,
+    /// Change to the hit chance of the attacks currently being added.
+    change: f64,
+    /// Change to the hit chance from the player being the target.
+    target_change: f64,
+}
+
+impl ThreatMap {
+    /// Work out the threats of every awake enemy in the map.
+    pub fn new(map: &bn::Map<En>) -> Self {
+        let mut threats = Self {
+            target_change: map.get_ent(unsafe { PLAYER }).map_or(0.0, accuracy::target_mod),
+            ..Self::default()
+        };
+
+        for (&pos, en) in map.get_entities() {
+            if en.is_player || en.dormant || en.is_dead() || en.special == Special::WallSentry {
+                continue;
+            }
+
+            threats.change =
+                accuracy::attacker_mod(map, en, pos, en.vel.is_some()) + threats.target_change;
+
+            if let Some(v) = en.vel {
+                threats.add_slide(map, en, pos, v);
+            } else if en.held() {
+                // Can't do anything next turn.
+            } else if let Some(act) = en.actions.get(en.count) {
+                threats.add_action(map, en, pos, act, 0);
+            }
+        }
+
+        // Don't give away anything about parts of the floor that can't be seen.
+        threats.tiles.retain(|p, _| fov::visible(*p));
+
+        threats
+    }
+
+    /// Mark a tile as being damaged by an attack with the given base accuracy.
+    fn add_dmg(&mut self, pos: Point, dmg: i32, acc: f64) {
+        let cur = self.tiles.entry(pos).or_insert(None);
+        *cur = Some(cur.unwrap_or(0) + dmg);
+
+        let chance = accuracy::chance(acc, self.change);
+        let cur = self.chances.entry(pos).or_insert(chance);
+        *cur = cur.min(chance);
+    }
+
+    /// Mark a tile as being passed through.
+    fn add_path(&mut self, pos: Point) {
+        self.tiles.entry(pos).or_insert(None);
+    }
+
+    /// Mark all the tiles hit by the given attack pattern's melee attacks in one direction, or
+    /// every direction if there is none.
+    fn add_melee(&mut self, atks: &AtkPat, pos: Point, dir: Option<Point>) {
+        for (atk_dir, atks) in atks.melee_atks.iter() {
+            if dir.is_some_and(|d| d != *atk_dir) {
+                continue;
+            }
+            for atk in atks {
+                for (p, dmg) in atk.damage_map(pos) {
+                    self.add_dmg(p, dmg, atk.acc());
+                }
+            }
+        }
+    }
+
+    /// Mark the tiles affected by an entity doing the given action.
+    fn add_action(&mut self, map: &bn::Map<En>, en: &En, pos: Point, act: &ActionType, depth: usize) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+
+        match act {
+            ActionType::TryMelee => self.add_melee(&en.atks, pos, None),
+            ActionType::ForceMelee(dir, idx) => {
+                if let Some(atk) = en.atks.melee_atks.get(dir).and_then(|a| a.get(*idx)) {
+                    for (p, dmg) in atk.damage_map(pos) {
+                        self.add_dmg(p, dmg, atk.acc());
+                    }
+                }
+            }
+            ActionType::TryMove(disp) => {
+                if en.special == Special::Missile {
+                    self.add_missile(map, en, pos, *disp);
+                } else {
+                    self.add_melee(&en.atks, pos, Some(*disp));
+                }
+            }
+            ActionType::Fire(idx) => {
+                if let Some(atk) = en.atks.ranged_atks.get(*idx) {
+                    self.add_ranged(map, pos, atk.range, effects_dmg(&atk.effects), atk.acc());
+                }
+            }
+            ActionType::Multi(a, b) | ActionType::Chain(a, b) => {
+                self.add_action(map, en, pos, a, depth + 1);
+                // Missiles explode once they can't move, which is already accounted for.
+                if en.special != Special::Missile {
+                    self.add_action(map, en, pos, b, depth + 1);
+                }
+            }
+            ActionType::Bridge(a, b) => {
+                self.add_action(map, en, pos, a, depth + 1);
+                // The second action only happens once the first has, which is usually a move.
+                if en.special != Special::Missile {
+                    let change = self.change;
+                    self.change = accuracy::attacker_mod(map, en, pos, true) + self.target_change;
+                    self.add_action(map, en, pos, b, depth + 1);
+                    self.change = change;
+                }
+            }
+            ActionType::Repeat(a) => self.add_action(map, en, pos, a, depth + 1),
+            ActionType::Jump(idx) => {
+                if let Some(a) = en.actions.get(*idx) {
+                    self.add_action(map, en, pos, a, depth + 1);
+                }
+            }
+            ActionType::CondBranch(idx_t, idx_f, _) => {
+                for idx in [idx_t, idx_f] {
+                    if let Some(a) = en.actions.get(*idx) {
+                        self.add_action(map, en, pos, a, depth + 1);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Mark every tile a ranged attack could reach from the given position.
+    fn add_ranged(&mut self, map: &bn::Map<En>, pos: Point, range: u32, dmg: i32, acc: f64) {
+        let r = range as i32;
+
+        for y in -r..=r {
+            for x in -r..=r {
+                let target = pos + Point::new(x, y);
+                if target == pos || pos.dist_squared(target) as u32 > range * range {
+                    continue;
+                }
+
+                if line_clear(map, pos, target) && map.get_map(target).is_some_and(|t| !t.blocking) {
+                    self.add_dmg(target, dmg, acc);
+                }
+            }
+        }
+    }
+
+    /// Mark the path of a missile moving in the given direction, and the explosion where it
+    /// will stop.
+    fn add_missile(&mut self, map: &bn::Map<En>, en: &En, pos: Point, dir: Point) {
+        let mut cur = pos;
+
+        for _ in 0..MAX_PATH {
+            let nx = cur + dir;
+            if !clear(map, nx) {
+                break;
+            }
+            cur = nx;
+            self.add_path(cur);
+        }
+
+        self.add_melee(&en.atks, cur, None);
+    }
+
+    /// Mark the path of an entity sliding across ice, along with anything it would attack if it
+    /// bumped into something.
+    fn add_slide(&mut self, map: &bn::Map<En>, en: &En, pos: Point, vel: Point) {
+        let mut cur = pos;
+
+        for _ in 0..MAX_PATH {
+            let nx = cur + vel;
+            if !clear(map, nx) {
+                self.add_melee(&en.atks, cur, Some(vel));
+                break;
+            }
+            cur = nx;
+            self.add_path(cur);
+            if !map.get_map(cur).is_some_and(|t| t.slippery) {
+                break;
+            }
+        }
+    }
+
+    /// Returns the character to show at a position given what is normally there.
+    pub fn shade(&self, pos: Point, ch: StyleCh, has_ent: bool) -> StyleCh {
+        match self.tiles.get(&pos) {
+            Some(Some(dmg)) => {
+                if has_ent {
+                    ch.on(DMG_CLR)
+                } else {
+                    let digit = char::from_digit(dmg.unsigned_abs().min(15), 16).unwrap();
+                    if *dmg >= 0 && self.chances.get(&pos).is_some_and(|c| *c < 1.0) {
+                        digit.with(UNSURE_CLR).on(DMG_CLR)
+                    } else if *dmg >= 0 {
+                        digit.white().on(DMG_CLR)
+                    } else {
+                        digit.green().on(DMG_CLR)
+                    }
+                }
+            }
+            Some(None) => ch.on(PATH_CLR),
+            None => ch,
+        }
+    }
+}
@@ -3,13 +3,8 @@
 use super::*;
 use crate::entity::*;
 
-/// The four arrows.
-pub const ARROWS: [char; 4] = ['↓', '←', '↑', '→'];
-/// This does look like a key when printed.
-pub const KEY: char = '⚷';
 /// Colour of the exit on each floor.
 pub const EXIT_CLRS: [style::Color; 4] = KEY_CLRS;
-pub const LOCKED_DOOR: char = '╬';
 type StepEffect = dyn StepEffectFn;
 
 /// Return a conveyor tile pushing entities that step on it in the given direction.
@@ -24,7 +19,7 @@ pub fn create_conveyor(disp: Point, revealed: bool) -> Tile {
         }));
     let dir = disp.dir();
     Tile {
-        ch: Some(ARROWS[dir].green()),
+        ch: Some(glyphs().arrows[dir].green()),
         blocking: false,
         empt: false,
         revealed,
@@ -32,6 +27,7 @@ pub fn create_conveyor(disp: Point, revealed: bool) -> Tile {
         door: false,
         step_effect,
         locked: None,
+        mechanism: None,
     }
 }
 
@@ -48,19 +44,19 @@ pub fn get_exit(revealed: bool, floor_num: usize) -> Tile {
             unsafe {
                 if ENEMIES_REMAINING == 0 {
                     NEXT_FLOOR = true;
-                    LOG_MSGS.write().unwrap().clear();
                 }
             }
             Vec::new()
         })),
         locked: None,
+        mechanism: None,
     }
 }
 
 /// Return a tile that provides the player with a key.
 pub fn get_key(revealed: bool, key_id: u32) -> Tile {
     Tile {
-        ch: Some(KEY.with(KEY_CLRS[key_id as usize % 4])),
+        ch: Some(glyphs().key.with(KEY_CLRS[key_id as usize % 4])),
         blocking: false,
         empt: false,
         revealed,
@@ -71,20 +67,53 @@ pub fn get_key(revealed: bool, key_id: u32) -> Tile {
             LOG_MSGS
                 .write()
                 .unwrap()
-                .push(format!("{} gains key", templates::PLAYER_CHARACTER).into());
+                .push(LogEvent::KeyGained(key_id).into());
             vec![bn::Cmd::new_on(pos).modify_tile(Box::new(|t: &mut Tile| {
                 t.step_effect = None;
-                t.ch = Some('.'.with(WALL_CLRS[unsafe { FLOORS_CLEARED as usize }]));
+                t.ch = Some('.'.with(get_wall_clr()));
             }))]
         })),
         locked: None,
+        mechanism: None,
+    }
+}
+
+/// Return a tile holding an item for the player to pick up.
+pub fn get_item(revealed: bool, item: items::Item) -> Tile {
+    Tile {
+        ch: Some(item.ch()),
+        blocking: false,
+        empt: false,
+        revealed,
+        door: false,
+        slippery: false,
+        step_effect: Some(Box::new(move |pos, _| {
+            vec![bn::Cmd::new_on(pos).modify_tile(Box::new(move |t: &mut Tile| {
+                // Enemies can walk over items without picking them up.
+                if unsafe { PLAYER } != pos {
+                    return;
+                }
+                if !items::add(item) {
+                    LOG_MSGS
+                        .write()
+                        .unwrap()
+                        .push(LogMsg::from(format!("{item} won't fit")));
+                    return;
+                }
+                LOG_MSGS.write().unwrap().push(LogEvent::ItemGained(item).into());
+                t.step_effect = None;
+                t.ch = Some('.'.with(get_wall_clr()));
+            }))]
+        })),
+        locked: None,
+        mechanism: None,
     }
 }
 
 /// Return a tile that is locked and requires a key of the correct id.
 pub fn get_locked_door(revealed: bool, key_id: u32) -> Tile {
     Tile {
-        ch: Some(LOCKED_DOOR.with(KEY_CLRS[key_id as usize % 4])),
+        ch: Some(glyphs().locked_door.with(KEY_CLRS[key_id as usize % 4])),
         blocking: true,
         empt: false,
         revealed,
@@ -92,6 +121,203 @@ pub fn get_locked_door(revealed: bool, key_id: u32) -> Tile {
         slippery: false,
         step_effect: None,
         locked: Some(key_id),
+        mechanism: None,
+    }
+}
+
+
+/// Character of a spike trap.
+pub const SPIKE_CHAR: char = '^';
+/// Character of a pressure plate.
+pub const PLATE_CHAR: char = '_';
+/// Character of a gate while it is held open.
+pub const OPEN_GATE_CHAR: char = '\'';
+/// Colour of spike traps, pressure plates and gates.
+pub const TRAP_CLR: style::Color = style::Color::DarkYellow;
+/// Colour of teleporters.
+pub const TELEPORTER_CLR: style::Color = style::Color::Magenta;
+/// Damage done to anything stepping on a spike trap.
+pub const SPIKE_DMG: u32 = 1;
+
+/// Return a spike trap that damages anything stepping on it.
+pub fn get_spikes(revealed: bool) -> Tile {
+    Tile {
+        ch: Some(SPIKE_CHAR.with(TRAP_CLR)),
+        empt: false,
+        revealed,
+        step_effect: Some(Box::new(|pos, _| {
+            vec![bn::Cmd::new_on(pos).modify_entity(Box::new(|e: &mut En| {
+                let inst = attacks::DmgInst::dmg(SPIKE_DMG, 1.0);
+                let old = *e.hp;
+                let amount = e.dmg_taken(inst);
+                e.apply_dmg(inst);
+
+                let mut handle = LOG_MSGS.write().unwrap();
+                handle.push(
+                    LogEvent::Damage {
+                        from: SPIKE_CHAR,
+                        to: *e.ch.content(),
+                        amount: amount as u32,
+                        kind: inst.kind,
+                    }
+                    .into(),
+                );
+                handle.push(LogMsg::hp_change(old, &e.hp, *e.ch.content()));
+            }))]
+        })),
+        mechanism: Some(Mechanism::Spikes),
+        ..Tile::default()
+    }
+}
+
+/// Return a teleporter sending anything that steps on it to the destination, if it is free.
+pub fn get_teleporter(revealed: bool, dest: Option<Point>) -> Tile {
+    Tile {
+        ch: Some(glyphs().teleporter.with(TELEPORTER_CLR)),
+        empt: false,
+        revealed,
+        step_effect: Some(Box::new(|pos, map: &bn::Map<En>| {
+            let Some(Mechanism::Teleporter(Some(dest))) = map.get_map(pos).and_then(|t| t.mechanism)
+            else {
+                return Vec::new();
+            };
+            if map.get_ent(dest).is_some() {
+                return Vec::new();
+            }
+            let mut cmds = force_move(map, pos, dest);
+            // The player's position has already been set to the teleporter by now.
+            cmds.push(bn::Cmd::new_on(dest).modify_entity(Box::new(move |e: &mut En| {
+                if e.is_player {
+                    unsafe { PLAYER = dest }
+                }
+            })));
+            cmds
+        })),
+        mechanism: Some(Mechanism::Teleporter(dest)),
+        ..Tile::default()
+    }
+}
+
+/// Return a pressure plate that holds the gate at the given position open while something is
+/// standing on it.
+pub fn get_plate(revealed: bool, gate: Option<Point>) -> Tile {
+    Tile {
+        ch: Some(PLATE_CHAR.with(TRAP_CLR)),
+        empt: false,
+        revealed,
+        step_effect: Some(Box::new(|pos, map: &bn::Map<En>| {
+            match map.get_map(pos).and_then(|t| t.mechanism) {
+                Some(Mechanism::Plate(Some(gate))) => {
+                    vec![bn::Cmd::new_on(gate).modify_tile(Box::new(open_gate))]
+                }
+                _ => Vec::new(),
+            }
+        })),
+        mechanism: Some(Mechanism::Plate(gate)),
+        ..Tile::default()
     }
 }
 
+/// Return a pressure plate that opens the gate at the given position for good. Once stepped on,
+/// neither the plate nor the gate are linked to anything, so the gate is never closed again.
+pub fn get_latch_plate(revealed: bool, gate: Option<Point>) -> Tile {
+    Tile {
+        step_effect: Some(Box::new(|pos, map: &bn::Map<En>| {
+            match map.get_map(pos).and_then(|t| t.mechanism) {
+                Some(Mechanism::Latch(Some(gate))) => vec![
+                    bn::Cmd::new_on(gate).modify_tile(Box::new(|t: &mut Tile| {
+                        open_gate(t);
+                        t.mechanism = Some(Mechanism::Gate(None));
+                    })),
+                    bn::Cmd::new_on(pos).modify_tile(Box::new(|t: &mut Tile| {
+                        t.mechanism = Some(Mechanism::Latch(None));
+                    })),
+                ],
+                _ => Vec::new(),
+            }
+        })),
+        mechanism: Some(Mechanism::Latch(gate)),
+        ..get_plate(revealed, gate)
+    }
+}
+
+/// Return a closed gate that the pressure plate at the given position opens.
+pub fn get_gate(revealed: bool, plate: Option<Point>) -> Tile {
+    let mut t = Tile {
+        empt: false,
+        revealed,
+        mechanism: Some(Mechanism::Gate(plate)),
+        ..Tile::default()
+    };
+    close_gate(&mut t);
+    t
+}
+
+/// Return a wall that is destroyed by explosions.
+pub fn get_cracked_wall(revealed: bool) -> Tile {
+    Tile {
+        ch: Some(glyphs().cracked_wall.with(get_wall_clr())),
+        empt: false,
+        blocking: true,
+        revealed,
+        mechanism: Some(Mechanism::Cracked),
+        ..Tile::default()
+    }
+}
+
+/// Let anything through the gate.
+fn open_gate(t: &mut Tile) {
+    t.blocking = false;
+    t.ch = Some(OPEN_GATE_CHAR.with(TRAP_CLR));
+}
+
+/// Stop anything going through the gate.
+fn close_gate(t: &mut Tile) {
+    t.blocking = true;
+    t.ch = Some(glyphs().gate.with(TRAP_CLR));
+}
+
+/// Returns the commands that close the gate linked to the tile an entity is leaving, if nothing
+/// else is holding it open. To is where the entity is going, or None if it is being removed.
+pub fn leave(map: &bn::Map<En>, from: Point, to: Option<Point>) -> Vec<bn::Cmd<En>> {
+    let (gate, plate) = match map.get_map(from).and_then(|t| t.mechanism) {
+        Some(Mechanism::Plate(Some(gate))) => (gate, from),
+        Some(Mechanism::Gate(Some(plate))) => (from, plate),
+        _ => return Vec::new(),
+    };
+    // A gate can have more than one plate, but only links back to one of them.
+    let linked = match map.get_map(gate).and_then(|t| t.mechanism) {
+        Some(Mechanism::Gate(Some(p))) => p,
+        _ => plate,
+    };
+
+    // The entity leaving is still in the map, so it doesn't count.
+    let held = |p: Point| Some(p) == to || (p != from && map.get_ent(p).is_some());
+    if held(gate) || held(plate) || held(linked) {
+        Vec::new()
+    } else {
+        vec![bn::Cmd::new_on(gate).modify_tile(Box::new(close_gate))]
+    }
+}
+
+/// Returns the commands that move whatever is at one position to another when it isn't moving
+/// itself, such as when it is pushed or teleported, along with any needed to let go of a plate it
+/// was holding down.
+pub fn force_move(map: &bn::Map<En>, from: Point, to: Point) -> Vec<bn::Cmd<En>> {
+    let mut cmds = leave(map, from, Some(to));
+    cmds.push(bn::Cmd::new_on(from).move_to(to));
+    cmds
+}
+
+/// Returns the commands that destroy the tile at the position if it is a cracked wall.
+pub fn crumble(map: &bn::Map<En>, pos: Point) -> Vec<bn::Cmd<En>> {
+    if map.get_map(pos).is_some_and(|t| t.mechanism == Some(Mechanism::Cracked)) {
+        vec![bn::Cmd::new_on(pos).modify_tile(Box::new(|t: &mut Tile| {
+            t.blocking = false;
+            t.ch = None;
+            t.mechanism = None;
+        }))]
+    } else {
+        Vec::new()
+    }
+}
@@ -0,0 +1,226 @@
+//! Walks the player across cleared parts of the floor without needing a key press for every step.
+
+use crate::{Point, Tile};
+use crate::bn;
+use crate::entity::{En, ENEMIES_REMAINING, LOG_MSGS, LogEvent, LogMsg, PLAYER};
+use rect::Rect;
+use std::collections::{HashMap, VecDeque};
+
+/// Most steps a single journey can take.
+pub const MAX_TRAVEL: usize = 200;
+
+/// Returns true if the tile can be walked over on the way somewhere else.
+fn passable(t: &Tile) -> bool {
+    t.revealed && !t.empt && !t.blocking && !t.slippery && t.step_effect.is_none()
+}
+
+/// Returns true if the tile can be the end of a journey.
+fn reachable(t: &Tile) -> bool {
+    t.revealed && !t.empt && !t.blocking && !t.slippery
+}
+
+/// Find the shortest path from one position to another, not including the start.
+pub fn find_path(map: &bn::Map<En>, from: Point, to: Point) -> Option<VecDeque<Point>> {
+    if !map.get_map(to).is_some_and(reachable) || map.get_ent(to).is_some() {
+        return None;
+    }
+
+    let mut came_from: HashMap<Point, Point> = HashMap::new();
+    let mut frontier = VecDeque::from([(from, 0)]);
+    came_from.insert(from, from);
+
+    while let Some((pos, dist)) = frontier.pop_front() {
+        if pos == to {
+            let mut path = VecDeque::new();
+            let mut cur = to;
+            while cur != from {
+                path.push_front(cur);
+                cur = came_from[&cur];
+            }
+            return Some(path);
+        }
+        if dist == MAX_TRAVEL {
+            continue;
+        }
+
+        for nx in pos.get_all_adjacent() {
+            if came_from.contains_key(&nx) || map.get_ent(nx).is_some() {
+                continue;
+            }
+            let Some(t) = map.get_map(nx) else {
+                continue;
+            };
+            if passable(t) || (nx == to && reachable(t)) {
+                came_from.insert(nx, pos);
+                frontier.push_back((nx, dist + 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// Returns every revealed door on the floor that leads somewhere that has not been revealed yet.
+pub fn unexplored_doors(map: &bn::Map<En>, rooms: &[Rect]) -> Vec<Point> {
+    let mut doors = Vec::new();
+
+    for r in rooms {
+        for p in r.cells() {
+            let Some(t) = map.get_map(p) else {
+                continue;
+            };
+            if t.door
+                && t.revealed
+                && !doors.contains(&p)
+                && p.get_all_adjacent()
+                    .into_iter()
+                    .any(|adj| map.get_map(adj).is_some_and(|a| !a.revealed))
+            {
+                doors.push(p);
+            }
+        }
+    }
+
+    doors
+}
+
+/// A journey the player is currently on.
+#[derive(Clone, Debug, Default)]
+pub struct Travel {
+    /// Positions still to be walked to.
+    path: VecDeque<Point>,
+    /// Door most recently travelled to using the door cycle.
+    last_door: Option<Point>,
+    /// Health of the player when the journey started.
+    hp: u32,
+}
+
+impl Travel {
+    /// Create a travel that is not going anywhere.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if the player is on a journey.
+    pub fn is_travelling(&self) -> bool {
+        !self.path.is_empty()
+    }
+
+    /// Stop travelling.
+    pub fn stop(&mut self) {
+        self.path.clear();
+    }
+
+    /// Start travelling to the given position. Only possible when there are no enemies around.
+    /// Returns true if there is a way there.
+    pub fn start(&mut self, map: &bn::Map<En>, to: Point) -> bool {
+        let pl_pos = unsafe { PLAYER };
+        if unsafe { ENEMIES_REMAINING } > 0 || to == pl_pos {
+            return false;
+        }
+
+        match find_path(map, pl_pos, to) {
+            Some(path) => {
+                self.path = path;
+                self.hp = map.get_ent(pl_pos).map(|e| *e.hp.value()).unwrap_or(0);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Start travelling to the next unexplored door, going from nearest to furthest each time
+    /// this is used. Returns true if there is one to go to.
+    pub fn next_door(&mut self, map: &bn::Map<En>, rooms: &[Rect]) -> bool {
+        let pl_pos = unsafe { PLAYER };
+        let mut doors = unexplored_doors(map, rooms);
+        doors.sort_by_key(|d| (d.manhattan_dist(pl_pos), d.x, d.y));
+
+        // Carry on from the last door picked, if it is still unexplored.
+        let first = match self.last_door.and_then(|l| doors.iter().position(|&d| d == l)) {
+            Some(idx) => idx + 1,
+            None => 0,
+        };
+
+        for n in 0..doors.len() {
+            let door = doors[(first + n) % doors.len()];
+            if self.start(map, door) {
+                self.last_door = Some(door);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Returns the displacement of the next step of the journey, or None if it has ended. Ends
+    /// the journey early if an enemy wakes up, the player is hurt, or they end up off course.
+    pub fn step(&mut self, map: &bn::Map<En>) -> Option<Point> {
+        let &nx = self.path.front()?;
+        let pl_pos = unsafe { PLAYER };
+        let hp = map.get_ent(pl_pos).map(|e| *e.hp.value()).unwrap_or(0);
+
+        let danger = unsafe { ENEMIES_REMAINING } > 0 || hp < self.hp;
+        if danger || nx.manhattan_dist(pl_pos) != 1 || map.get_ent(nx).is_some() {
+            self.stop();
+            if danger {
+                LOG_MSGS
+                    .write()
+                    .unwrap()
+                    .push(LogMsg::new(LogEvent::TravelStopped));
+            }
+            return None;
+        }
+
+        self.path.pop_front();
+        Some(nx - pl_pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tile_presets;
+
+    /// Returns a revealed floor of the given size with its bottom left corner at the origin.
+    fn floor(wid: i32, hgt: i32) -> bn::Map<En> {
+        let mut map = bn::Map::new(0, 0);
+        for x in 0..wid {
+            for y in 0..hgt {
+                map.insert_tile(Tile { empt: false, revealed: true, ..Tile::default() }, Point::new(x, y));
+            }
+        }
+        map
+    }
+
+    #[test]
+    fn find_path() {
+        // A wall down the middle with a gap at the top.
+        let mut map = floor(5, 3);
+        for y in 0..2 {
+            map.insert_tile(
+                Tile { empt: false, revealed: true, blocking: true, ..Tile::default() },
+                Point::new(2, y),
+            );
+        }
+        let path = super::find_path(&map, Point::new(0, 0), Point::new(4, 0)).unwrap();
+        assert_eq!(path.len(), 8);
+        assert_eq!(path.back(), Some(&Point::new(4, 0)));
+        assert!(path.contains(&Point::new(2, 2)));
+
+        // Tiles that do something when stepped on are walked around, but can still be walked to.
+        map.insert_tile(tile_presets::get_key(true, 0), Point::new(2, 2));
+        assert!(super::find_path(&map, Point::new(0, 0), Point::new(4, 0)).is_none());
+        assert_eq!(
+            super::find_path(&map, Point::new(0, 0), Point::new(2, 2)).map(|p| p.len()),
+            Some(4)
+        );
+
+        // Nothing unrevealed or occupied can be walked to.
+        let mut map = floor(3, 1);
+        map.get_map_mut(Point::new(2, 0)).unwrap().revealed = false;
+        assert!(super::find_path(&map, Point::new(0, 0), Point::new(2, 0)).is_none());
+        map.insert_entity(crate::templates::get_player(), Point::new(1, 0));
+        assert!(super::find_path(&map, Point::new(0, 0), Point::new(1, 0)).is_none());
+    }
+}
@@ -2,8 +2,9 @@
 
 use crate::Point;
 use crate::bn;
+use crate::layout::Layout;
 use bn::windowed;
-use crossterm::{cursor, event, queue, style};
+use crossterm::{cursor, event, queue, style, terminal};
 use dyn_clone::{DynClone, clone_trait_object};
 use std::collections::HashMap;
 use std::io::{self, Write};
@@ -45,6 +46,21 @@ pub trait UiElement: DynClone {
 
     /// Return the on screen position of this element.
     fn true_pos(&self) -> Point;
+
+    /// Returns true if the cursor can be moved onto this element.
+    fn selectable(&self) -> bool {
+        true
+    }
+
+    /// Returns true if the element is drawn over the given position in the window, given an
+    /// offset. Elements that can't be pointed at with the mouse never are.
+    fn hit(&self, _pos: Point, _offset: Point) -> bool {
+        false
+    }
+
+    /// Move the element to suit a new layout of the terminal. Only needed by elements that are
+    /// placed on the screen directly rather than inside the scene.
+    fn recentre(&mut self, _lay: &Layout) {}
 }
 
 clone_trait_object! {UiElement}
@@ -68,6 +84,7 @@ pub struct Scene {
     elements: HashMap<Point, Box<dyn UiElement>>,
     cursor: Point,
     cont: windowed::Container<StyleCh>,
+    top_left: Point,
     wid: usize,
     hgt: usize,
     scrolling: bool,
@@ -84,6 +101,7 @@ impl Scene {
             elements: HashMap::new(),
             cursor: Point::ORIGIN,
             cont,
+            top_left,
             wid,
             hgt,
             scrolling: false,
@@ -116,10 +134,11 @@ impl Scene {
         self.elements.get(&pos).map(|v| &**v)
     }
 
-    /// Move the cursor to the given position if there is an element there. Returns true if it is
-    /// successful.
+    /// Move the cursor to the given position if there is an element there that can be selected.
+    /// Returns true if it is successful.
     pub fn move_cursor(&mut self, new_pos: Point) -> bool {
-        if self.try_hover(new_pos) {
+        let selectable = self.elements.get(&new_pos).is_some_and(|elem| elem.selectable());
+        if selectable && self.try_hover(new_pos) {
             self.try_hover(self.cursor);
             self.cursor = new_pos;
             true
@@ -157,6 +176,75 @@ impl Scene {
         self.cont.refresh();
     }
 
+    /// Returns the navigation position of the element under the given terminal position.
+    fn element_at(&self, screen_pos: Point) -> Option<Point> {
+        // An outline around the scene moves everything inside it across and down.
+        let border = (self.cont.windows[0].data.len() as i32 - self.hgt as i32) / 2;
+        let pos = screen_pos - self.top_left - Point::new(border, border);
+
+        // Anything outside the scene has been scrolled out of sight.
+        if pos.x < 0 || pos.y < 0 || pos.x >= self.wid as i32 || pos.y >= self.hgt as i32 {
+            return None;
+        }
+
+        self.elements
+            .iter()
+            .find(|(_p, elem)| elem.hit(pos, self.offset))
+            .map(|(&p, _elem)| p)
+    }
+
+    /// Work out what a mouse event should do in this scene.
+    fn mouse_nav(&self, me: event::MouseEvent) -> Nav {
+        let screen_pos = Point::new(me.column as i32, me.row as i32);
+
+        match me.kind {
+            event::MouseEventKind::ScrollDown => Nav::Move(Point::new(0, 1)),
+            event::MouseEventKind::ScrollUp => Nav::Move(Point::new(0, -1)),
+            event::MouseEventKind::Moved => match self.element_at(screen_pos) {
+                Some(p) => Nav::Hover(p),
+                None => Nav::Null,
+            },
+            event::MouseEventKind::Down(event::MouseButton::Left) => {
+                match self.element_at(screen_pos) {
+                    Some(p) => Nav::Click(p),
+                    None => Nav::Null,
+                }
+            }
+            _ => Nav::Null,
+        }
+    }
+
+    /// Forget what has already been printed so that the next draw prints the whole scene again.
+    fn forget_printed(&mut self) {
+        let mut cont = windowed::Container::new();
+        for win in &self.cont.windows {
+            cont.add_win(win.clone());
+        }
+        self.cont = cont;
+    }
+
+    /// Move the scene back to the middle of the terminal, at the same height, after it has been
+    /// resized.
+    fn recentre(&mut self, lay: &Layout) {
+        self.top_left = Point::new(lay.centred(self.wid), self.top_left.y);
+        self.cont = windowed::Container::new();
+        self.cont.add_win(windowed::Window::new(self.top_left));
+
+        for elem in self.elements.values_mut() {
+            elem.recentre(lay);
+        }
+    }
+
+    /// Make the next draw print everything in the scene again, such as after the screen has
+    /// been cleared.
+    fn reprint(&mut self) {
+        // Titles print themselves once, so they need telling to do so again.
+        for elem in self.elements.values_mut() {
+            elem.receive("clr");
+        }
+        self.forget_printed();
+    }
+
     /// Toggle the hover state of the element at the given position if there is one. Returns true
     /// if an element is hovered, otherwise false.
     fn try_hover(&mut self, pos: Point) -> bool {
@@ -183,6 +271,10 @@ impl Scene {
 
 enum Nav {
     Move(Point),
+    /// Move the cursor to the element at this position.
+    Hover(Point),
+    /// Move the cursor to the element at this position and activate it.
+    Click(Point),
     Activate,
     Null,
 }
@@ -225,9 +317,22 @@ impl UiContainer {
         self.cur = new_idx;
     }
 
+    /// Print the whole of the current scene again the next time it runs, for when something
+    /// else has cleared the screen.
+    pub fn reprint(&mut self) {
+        self.cur_scene_mut().reprint();
+    }
+
     /// Start displaying the UI elements into the current scene. Updates this window every key
     /// press. Exits with a user defined code when an element causes this to happen.
     pub fn run(&mut self) -> u32 {
+        self.run_with(|_| ())
+    }
+
+    /// Same as run, but calls redraw with the new layout after the terminal is resized and the
+    /// screen cleared, so that anything displayed around the scene can be moved and displayed
+    /// again.
+    pub fn run_with<F: FnMut(&Layout)>(&mut self, mut redraw: F) -> u32 {
         let mut handle = io::stdout();
 
         'full: loop {
@@ -241,18 +346,24 @@ impl UiContainer {
 
             let _ = handle.flush();
 
-            while let event::Event::Key(ke) = event::read().expect("what") {
-                if ke.is_press() {
-                    let code = ke.code;
+            loop {
+                let action = match event::read().expect("what") {
+                    event::Event::Key(ke) => {
+                        if !ke.is_press() {
+                            continue;
+                        }
 
-                    // Check if the element wants text input before doing navigation.
-                    if !scene
-                        .elements
-                        .get_mut(&scene.cursor)
-                        .unwrap()
-                        .receive_text(code)
-                    {
-                        let action = match ke.code {
+                        // Check if the element wants text input before doing navigation.
+                        if scene
+                            .elements
+                            .get_mut(&scene.cursor)
+                            .unwrap()
+                            .receive_text(ke.code)
+                        {
+                            continue 'full;
+                        }
+
+                        match ke.code {
                             // Has arrow keys, wasd, and, for the vim users among us, hjkl.
                             event::KeyCode::Left
                             | event::KeyCode::Char('a')
@@ -268,55 +379,84 @@ impl UiContainer {
                             | event::KeyCode::Char('k') => Nav::Move(Point::new(0, -1)),
                             event::KeyCode::Enter | event::KeyCode::Char(' ') => Nav::Activate,
                             _ => Nav::Null,
-                        };
-
-                        match action {
-                            Nav::Move(p) => {
-                                if scene.scrolling {
-                                    scene.scrolling_move(p);
-                                } else {
-                                    scene.disp_cursor(p);
-                                }
-                            }
-                            Nav::Activate => {
-                                let evs = scene
-                                    .elements
-                                    .get_mut(&scene.cursor)
-                                    .expect("No ui elements to activate")
-                                    .activate();
-                                let mut new_idx = None;
-
-                                for ev in evs {
-                                    match ev {
-                                        Event::Exit(code) => return code,
-                                        Event::ChangeScene(idx) => {
-                                            // Hide the evidence when we change scene.
-                                            for p in scene.cont.changed() {
-                                                let _ = queue!(handle, cursor::MoveTo(p.x as u16, p.y as u16), style::Print(' '));
-                                            }
-
-                                            let _ = handle.flush();
-
-                                            new_idx = Some(idx);
-                                        }
-                                        Event::Broadcast(d) => {
-                                            for elem in scene.elements.values_mut() {
-                                                elem.receive(&d);
-                                            }
-                                        }
-                                        Event::Null => (),
-                                    }
+                        }
+                    }
+                    event::Event::Mouse(me) => scene.mouse_nav(me),
+                    event::Event::Resize(..) => {
+                        let lay = Layout::wait_for_fit();
+                        let _ = queue!(handle, terminal::Clear(terminal::ClearType::All));
+                        redraw(&lay);
+
+                        // Every scene is moved, so switching to another later finds it centred.
+                        for sc in self.scenes.iter_mut() {
+                            sc.recentre(&lay);
+                        }
+                        self.cur_scene_mut().reprint();
+                        continue 'full;
+                    }
+                    _ => continue,
+                };
+
+                let activate = match action {
+                    Nav::Move(p) => {
+                        if scene.scrolling {
+                            scene.scrolling_move(p);
+                        } else {
+                            scene.disp_cursor(p);
+                        }
+                        false
+                    }
+                    Nav::Hover(p) => {
+                        // Don't redraw every time the mouse moves within the same element.
+                        if p == scene.cursor {
+                            continue;
+                        }
+                        scene.move_cursor(p);
+                        false
+                    }
+                    Nav::Click(p) => {
+                        scene.move_cursor(p);
+                        true
+                    }
+                    Nav::Activate => true,
+                    Nav::Null => continue,
+                };
+
+                if activate {
+                    let evs = scene
+                        .elements
+                        .get_mut(&scene.cursor)
+                        .expect("No ui elements to activate")
+                        .activate();
+                    let mut new_idx = None;
+
+                    for ev in evs {
+                        match ev {
+                            Event::Exit(code) => return code,
+                            Event::ChangeScene(idx) => {
+                                // Hide the evidence when we change scene.
+                                for p in scene.cont.changed() {
+                                    let _ = queue!(handle, cursor::MoveTo(p.x as u16, p.y as u16), style::Print(' '));
                                 }
 
-                                if let Some(idx) = new_idx {
-                                    self.cur = idx;
+                                let _ = handle.flush();
+
+                                new_idx = Some(idx);
+                            }
+                            Event::Broadcast(d) => {
+                                for elem in scene.elements.values_mut() {
+                                    elem.receive(&d);
                                 }
                             }
-                            Nav::Null => continue,
+                            Event::Null => (),
                         }
                     }
-                    continue 'full;
+
+                    if let Some(idx) = new_idx {
+                        self.cur = idx;
+                    }
                 }
+                continue 'full;
             }
         }
     }
@@ -157,6 +157,179 @@ impl UiElement for Button {
     fn true_pos(&self) -> Point {
         self.screen_pos
     }
+
+    // Buttons that do nothing are just labels, so the mouse ignores them.
+    fn hit(&self, pos: Point, offset: Point) -> bool {
+        let start = self.screen_pos + offset;
+        let len = (self.selector.chars().count() + self.txt.chars().count()) as i32;
+
+        self.event.iter().any(|ev| !matches!(ev, Event::Null))
+            && pos.y == start.y
+            && pos.x >= start.x
+            && pos.x < start.x + len
+    }
+}
+
+/// Text shown in a scene that can't be selected or clicked.
+#[derive(Clone, Debug)]
+pub struct Label {
+    txt: String,
+    clr: style::Color,
+    screen_pos: Point,
+}
+
+impl Label {
+    /// Create a new white label.
+    pub fn new(txt: String, screen_pos: Point) -> Self {
+        Self {
+            txt,
+            clr: style::Color::White,
+            screen_pos,
+        }
+    }
+
+    field_builder! {Label, clr; style::Color}
+}
+
+impl UiElement for Label {
+    fn receive(&mut self, _data: &str) {}
+
+    fn activate(&mut self) -> Vec<Event> {
+        vec![Event::Null]
+    }
+
+    fn display_into(&self, win: &mut windowed::Window<StyleCh>, offset: Point) {
+        let txt: Vec<StyleCh> = self.txt.chars().map(|ch| ch.with(self.clr)).collect();
+        put_text(&txt, win, self.screen_pos + offset);
+    }
+
+    fn toggle_hover(&mut self) {}
+
+    fn get_text(&self) -> String {
+        self.txt.clone()
+    }
+
+    fn true_pos(&self) -> Point {
+        self.screen_pos
+    }
+
+    fn selectable(&self) -> bool {
+        false
+    }
+}
+
+/// A button that switches between on and off when activated. Its text is "yes" or "no" depending
+/// on whether it is on.
+#[derive(Clone, Debug)]
+pub struct Toggle {
+    txt: String,
+    on: bool,
+    clr: style::Color,
+    hover_clr: style::Color,
+    on_clr: style::Color,
+    selector: String,
+    selector_clr: style::Color,
+    hover: bool,
+    screen_pos: Point,
+}
+
+impl Toggle {
+    /// Return a toggle that is off.
+    pub fn new(txt: String) -> Self {
+        Self {
+            txt,
+            ..Self::default()
+        }
+    }
+
+    field_builder! {Toggle, on}
+    field_builder! {Toggle, clr; style::Color}
+    field_builder! {Toggle, hover_clr; style::Color}
+    field_builder! {Toggle, on_clr; style::Color}
+    field_builder! {Toggle, selector; String}
+    field_builder! {Toggle, selector_clr; style::Color}
+    field_builder! {Toggle, screen_pos; Point}
+
+    /// Return the text that would be displayed by this toggle currently.
+    fn get_text(&self) -> Vec<StyleCh> {
+        let mut data = Vec::new();
+
+        for ch in self.selector.chars() {
+            data.push(if self.hover {
+                ch.with(self.selector_clr)
+            } else {
+                ' '.stylize()
+            });
+        }
+
+        let clr = if self.hover {
+            self.hover_clr
+        } else if self.on {
+            self.on_clr
+        } else {
+            self.clr
+        };
+        let txt = format!("[{}] {}", if self.on { 'x' } else { ' ' }, self.txt);
+        data.extend(txt.chars().map(|ch| ch.with(clr)));
+
+        data
+    }
+}
+
+impl Default for Toggle {
+    fn default() -> Self {
+        Self {
+            txt: String::new(),
+            on: false,
+            clr: style::Color::White,
+            hover_clr: style::Color::Rgb {
+                r: 255,
+                g: 190,
+                b: 0,
+            },
+            on_clr: style::Color::Green,
+            selector: String::from(">"),
+            selector_clr: style::Color::Rgb {
+                r: 255,
+                g: 190,
+                b: 0,
+            },
+            hover: false,
+            screen_pos: Point::ORIGIN,
+        }
+    }
+}
+
+impl UiElement for Toggle {
+    fn receive(&mut self, _data: &str) {}
+
+    fn activate(&mut self) -> Vec<Event> {
+        self.on = !self.on;
+        vec![Event::Null]
+    }
+
+    fn display_into(&self, win: &mut windowed::Window<StyleCh>, offset: Point) {
+        put_text(&self.get_text(), win, self.screen_pos + offset);
+    }
+
+    fn toggle_hover(&mut self) {
+        self.hover = !self.hover;
+    }
+
+    fn get_text(&self) -> String {
+        String::from(if self.on { "yes" } else { "no" })
+    }
+
+    fn true_pos(&self) -> Point {
+        self.screen_pos
+    }
+
+    fn hit(&self, pos: Point, offset: Point) -> bool {
+        let start = self.screen_pos + offset;
+        let len = (self.selector.chars().count() + self.txt.chars().count() + 4) as i32;
+
+        pos.y == start.y && pos.x >= start.x && pos.x < start.x + len
+    }
 }
 
 /// Creates an outline around the window. Position is arbitrary. Ensures the resulting window
@@ -361,6 +534,11 @@ impl UiElement for TextEntry {
     fn true_pos(&self) -> Point {
         self.screen_pos
     }
+
+    fn hit(&self, pos: Point, offset: Point) -> bool {
+        let start = self.screen_pos + offset;
+        pos.y == start.y && pos.x >= start.x && pos.x < start.x + self.len as i32
+    }
 }
 
 fn put_text(txt: &[StyleCh], win: &mut windowed::Window<StyleCh>, pos: Point) {
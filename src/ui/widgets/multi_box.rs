@@ -225,6 +225,21 @@ impl UiElement for MultiBox {
     // Not intended to be selected.
     fn toggle_hover(&mut self) {}
 
+    fn hit(&self, pos: Point, offset: Point) -> bool {
+        let mut start = self.screen_pos;
+        if self.scrolls {
+            start = start + offset;
+        }
+        let rel = pos - start;
+        let data = &self.get_state().data;
+
+        rel.y >= 0
+            && rel.x >= 0
+            && data
+                .get(rel.y as usize)
+                .is_some_and(|row| (rel.x as usize) < row.len())
+    }
+
     fn display_into(&self, win: &mut windowed::Window<StyleCh>, offset: Point) {
         let cur_state = self.get_state();
 
@@ -78,9 +78,14 @@ impl UiElement for Title {
         }
         
         let mut handle = io::stdout();
+        // Anything past the edge of the terminal would wrap onto the next line.
+        let term_wid = terminal::size().map(|(w, _h)| w as usize).unwrap_or(usize::MAX);
 
         for (y, line) in self.win.data.iter().enumerate() {
             for (x, ch) in line.iter().enumerate() {
+                if x + self.screen_pos.x as usize >= term_wid {
+                    break;
+                }
                 let _ = queue!(handle, cursor::MoveTo(x as u16 + self.screen_pos.x as u16, y as u16 + self.screen_pos.y as u16), style::Print(ch));
             }
 
@@ -94,4 +99,9 @@ impl UiElement for Title {
     }
 
     fn toggle_hover(&mut self) {}
+
+    fn recentre(&mut self, lay: &Layout) {
+        let wid = self.win.data.iter().map(|ln| ln.len()).max().unwrap_or(0);
+        self.screen_pos = Point::new(lay.centred(wid), self.screen_pos.y);
+    }
 }
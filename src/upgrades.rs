@@ -0,0 +1,165 @@
+//! Upgrades the player chooses between after completing a floor.
+
+use crate::{Point, templates};
+use crate::classes::{self, Class};
+use crate::attacks::{Effect, FOUR_POS_ATK};
+use crate::entity::En;
+use crate::modifiers::{self, Modifier};
+use crate::status::{self, Status, StatusKind};
+use crossterm::style;
+use rand::{Rng, seq::SliceRandom};
+use std::{fmt, sync::RwLock};
+
+/// Most upgrades offered at once.
+pub const DRAFT_SIZE: usize = 3;
+/// Highest max health that can be reached with upgrades.
+const MAX_HP_CAP: u32 = 15;
+/// How long the vfx of upgraded attacks last.
+const ATK_DURATION: usize = 7;
+
+/// Upgrades the player has taken this run, in the order they were taken.
+pub static TAKEN: RwLock<Vec<Upgrade>> = RwLock::new(Vec::new());
+
+/// Something that permanently changes the player for the rest of the run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Upgrade {
+    /// One more max health.
+    MaxHp,
+    /// Moving towards an enemy diagonally in front of the player attacks it.
+    Sweep,
+    /// Attacks knock enemies back.
+    Heavy,
+    /// The player can leap two tiles in a straight line.
+    Leap,
+    /// The player can zap a nearby enemy for lots of damage.
+    Wand,
+    /// The player can fire a laser that hits everything in a line.
+    Laser,
+    /// Melee attacks poison what they hit.
+    Venom,
+}
+
+impl Upgrade {
+    /// Every upgrade, in the order they are offered.
+    pub const ALL: [Upgrade; 7] = [
+        Upgrade::MaxHp,
+        Upgrade::Sweep,
+        Upgrade::Heavy,
+        Upgrade::Leap,
+        Upgrade::Wand,
+        Upgrade::Laser,
+        Upgrade::Venom,
+    ];
+
+    /// Returns the colour the upgrade is shown in.
+    pub fn clr(self) -> style::Color {
+        match self {
+            Upgrade::MaxHp => style::Color::Green,
+            Upgrade::Sweep | Upgrade::Heavy => style::Color::Red,
+            Upgrade::Leap => style::Color::Cyan,
+            Upgrade::Wand | Upgrade::Laser => style::Color::Magenta,
+            Upgrade::Venom => StatusKind::Poison.clr(),
+        }
+    }
+
+    /// Returns true if the upgrade would do anything for the player.
+    fn available(self, pl: &En) -> bool {
+        match self {
+            Upgrade::MaxHp => !modifiers::active(Modifier::OneHp) && pl.hp.max < MAX_HP_CAP,
+            // Brutes already knock enemies back.
+            Upgrade::Heavy if classes::current() == Class::Brute => false,
+            Upgrade::Leap if pl.movement.contains(&Point::new(0, 2)) => false,
+            _ => !TAKEN.read().unwrap().contains(&self),
+        }
+    }
+
+    /// Change the player to have this upgrade.
+    pub fn apply(self, pl: &mut En) {
+        match self {
+            Upgrade::MaxHp => {
+                let max = pl.hp.max + 1;
+                pl.hp.change_max(max);
+                pl.hp += 1;
+            }
+            Upgrade::Sweep => {
+                // Diagonal attacks are done by moving in either direction next to the diagonal,
+                // since the player can only move orthogonally.
+                let diag = templates::get_diag_atks(1, style::Color::Red, 1, ATK_DURATION, true);
+                for (dir, atks) in diag.melee_atks {
+                    for side in [Point::new(dir.x, 0), Point::new(0, dir.y)] {
+                        pl.atks.melee_atks.entry(side).or_default().extend(atks.iter().cloned());
+                    }
+                }
+            }
+            Upgrade::Heavy => {
+                let dmg = pl
+                    .atks
+                    .melee_atks
+                    .get(&Point::new(0, 1))
+                    .and_then(|atks| atks.first())
+                    .and_then(|atk| atk.damage_map(Point::ORIGIN).into_values().max())
+                    .map_or(1, |dmg| dmg.max(1) as u32);
+                let hvy = templates::get_hvy_atks(dmg, FOUR_POS_ATK, style::Color::Red);
+
+                // Replace the basic attack in each direction, keeping anything else.
+                for (dir, mut atks) in hvy.melee_atks {
+                    let cur = pl.atks.melee_atks.entry(dir).or_default();
+                    if !cur.is_empty() {
+                        cur.remove(0);
+                    }
+                    atks.append(cur);
+                    *cur = atks;
+                }
+            }
+            Upgrade::Leap => pl.movement.extend(templates::get_manhattan_n(2)),
+            Upgrade::Wand => pl.atks.ranged_atks.push(templates::get_wand(2, 3)),
+            Upgrade::Laser => pl.atks.ranged_atks.push(templates::get_laser(1, 6)),
+            Upgrade::Venom => (),
+        }
+
+        // Attacks gained after taking venom are poisoned too.
+        if self == Upgrade::Venom || TAKEN.read().unwrap().contains(&Upgrade::Venom) {
+            add_venom(pl);
+        }
+
+        TAKEN.write().unwrap().push(self);
+    }
+}
+
+impl fmt::Display for Upgrade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Upgrade::MaxHp => "+1 Max HP",
+            Upgrade::Sweep => "Sweeping Strikes",
+            Upgrade::Heavy => "Heavy Blows",
+            Upgrade::Leap => "Leaping",
+            Upgrade::Wand => "Wand",
+            Upgrade::Laser => "Laser",
+            Upgrade::Venom => "Venom",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Make every melee attack of the player poison what it hits, if it doesn't already.
+fn add_venom(pl: &mut En) {
+    let poison = Status::new(StatusKind::Poison, status::VENOM_POISON);
+    for atk in pl.atks.melee_atks.values_mut().flatten() {
+        let poisoned = atk
+            .effects
+            .iter()
+            .any(|ef| matches!(ef, Effect::Inflict(st) if st.kind == StatusKind::Poison));
+        if !poisoned {
+            atk.effects.push(Effect::Inflict(poison));
+        }
+    }
+}
+
+/// Returns up to DRAFT_SIZE different upgrades for the player to choose between, picked with the
+/// given rng so that seeded runs are offered the same upgrades.
+pub fn draft<R: Rng>(pl: &En, rng: &mut R) -> Vec<Upgrade> {
+    let mut choices: Vec<Upgrade> = Upgrade::ALL.into_iter().filter(|u| u.available(pl)).collect();
+    choices.shuffle(rng);
+    choices.truncate(DRAFT_SIZE);
+    choices
+}